@@ -0,0 +1 @@
+pub mod workspace_backup;
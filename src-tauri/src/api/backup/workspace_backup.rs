@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::api::chats::chat_storage::{
+    insert_chats, load_chat_data, load_chats_index, ChatData, ChatMeta,
+};
+use crate::api::folders::folders_storage::{load_folders_index, save_folders_index, FolderMeta};
+use crate::api::rag::document_index::{load_document_index, save_document_index, DocumentIndex};
+use crate::api::workspace::workspace_storage::{
+    load_workspaces_index, save_workspaces_index, WorkspaceMeta,
+};
+
+/// Identifier written into backup manifests so restore can recognise the archive.
+const BACKUP_FORMAT_ID: &str = "oalpaca-workspace-backup";
+/// Version of the backup archive layout.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Archive paths of the fixed entries in a backup.
+const MANIFEST_PATH: &str = "manifest.json";
+const WORKSPACE_PATH: &str = "workspace.json";
+const FOLDERS_PATH: &str = "folders.json";
+const CHATS_INDEX_PATH: &str = "chats_index.json";
+const DOCUMENT_INDEX_PATH: &str = "documents/document_index.json";
+
+/// A file contained in a backup archive with its size and SHA-256 checksum.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupFileEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// The manifest stored at the root of every backup archive. Lists every other entry with its checksum.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub chat_count: usize,
+    pub folder_count: usize,
+    pub files: Vec<BackupFileEntry>,
+}
+
+/// Summary of a restore: the workspace that was created and how many IDs had to be remapped because they already existed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreReport {
+    pub workspace: WorkspaceMeta,
+    pub folders_restored: usize,
+    pub chats_restored: usize,
+    pub remapped_ids: usize,
+}
+
+/// Returns the current UTC time as an ISO 8601 RFC3339 string. Used for the manifest timestamp.
+fn now_iso() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Returns the hex-encoded SHA-256 of the given bytes.
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Serializes a value as pretty JSON bytes for an archive entry.
+fn to_json_bytes<T: Serialize>(value: &T, what: &str) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize {}: {}", what, e))
+}
+
+/// Parses an archive entry that has already passed checksum verification.
+fn from_json_bytes<T: for<'de> Deserialize<'de>>(
+    entries: &HashMap<String, Vec<u8>>,
+    path: &str,
+) -> Result<T, String> {
+    let bytes = entries
+        .get(path)
+        .ok_or_else(|| format!("Backup is missing '{}'", path))?;
+    serde_json::from_slice(bytes)
+        .map_err(|e| format!("Failed to parse '{}' in backup: {}", path, e))
+}
+
+/// Tauri command: Writes a workspace with its folders, chat index entries, chat files and document index into a single zip archive with a checksummed manifest.
+#[tauri::command]
+pub async fn backup_workspace(
+    workspace_id: String,
+    path: String,
+) -> Result<BackupManifest, String> {
+    let workspace = load_workspaces_index()?
+        .workspaces
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| format!("Workspace with id '{}' not found", workspace_id))?;
+    let folders: Vec<FolderMeta> = load_folders_index()?
+        .folders
+        .into_iter()
+        .filter(|f| f.workspace_id == workspace_id)
+        .collect();
+    let chats: Vec<ChatMeta> = load_chats_index()?
+        .chats
+        .into_iter()
+        .filter(|c| c.workspace_id == workspace_id)
+        .collect();
+
+    let mut entries: Vec<(String, Vec<u8>)> = vec![
+        (
+            WORKSPACE_PATH.to_string(),
+            to_json_bytes(&workspace, "workspace")?,
+        ),
+        (
+            FOLDERS_PATH.to_string(),
+            to_json_bytes(&folders, "folders")?,
+        ),
+        (
+            CHATS_INDEX_PATH.to_string(),
+            to_json_bytes(&chats, "chats index")?,
+        ),
+    ];
+    for chat in &chats {
+        let data = load_chat_data(&chat.id)?;
+        entries.push((
+            format!("chats/{}.json", chat.id),
+            to_json_bytes(&data, "chat data")?,
+        ));
+    }
+    if let Some(document_index) = load_document_index(&workspace_id)? {
+        entries.push((
+            DOCUMENT_INDEX_PATH.to_string(),
+            to_json_bytes(&document_index, "document index")?,
+        ));
+    }
+
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT_ID.to_string(),
+        version: BACKUP_FORMAT_VERSION,
+        created_at: now_iso(),
+        workspace_id: workspace.id.clone(),
+        workspace_name: workspace.name.clone(),
+        chat_count: chats.len(),
+        folder_count: folders.len(),
+        files: entries
+            .iter()
+            .map(|(path, bytes)| BackupFileEntry {
+                path: path.clone(),
+                size: bytes.len() as u64,
+                sha256: sha256_hex(bytes),
+            })
+            .collect(),
+    };
+
+    let file = File::create(&path)
+        .map_err(|e| format!("Failed to create backup file '{}': {}", path, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let manifest_bytes = to_json_bytes(&manifest, "backup manifest")?;
+    for (entry_path, bytes) in std::iter::once((MANIFEST_PATH, &manifest_bytes))
+        .chain(entries.iter().map(|(p, b)| (p.as_str(), b)))
+    {
+        zip.start_file(entry_path, options)
+            .map_err(|e| format!("Failed to add '{}' to backup: {}", entry_path, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write '{}' to backup: {}", entry_path, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize backup archive: {}", e))?;
+
+    Ok(manifest)
+}
+
+/// Reads every file entry of a zip archive into memory.
+fn read_archive(path: &str) -> Result<HashMap<String, Vec<u8>>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open backup file '{}': {}", path, e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("Failed to read backup archive: {}", e))?;
+
+    let mut entries = HashMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read backup entry {}: {}", i, e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read '{}' from backup: {}", name, e))?;
+        entries.insert(name, bytes);
+    }
+    Ok(entries)
+}
+
+/// Checks that every file listed in the manifest is present with a matching size and checksum.
+fn verify_manifest(
+    manifest: &BackupManifest,
+    entries: &HashMap<String, Vec<u8>>,
+) -> Result<(), String> {
+    if manifest.format != BACKUP_FORMAT_ID {
+        return Err("This file is not an Oalpaca workspace backup".to_string());
+    }
+    if manifest.version > BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "Backup format version {} is newer than this app supports ({})",
+            manifest.version, BACKUP_FORMAT_VERSION
+        ));
+    }
+
+    for file in &manifest.files {
+        let bytes = entries
+            .get(&file.path)
+            .ok_or_else(|| format!("Backup is missing '{}'", file.path))?;
+        if bytes.len() as u64 != file.size || sha256_hex(bytes) != file.sha256 {
+            return Err(format!(
+                "Checksum mismatch for '{}': the backup is corrupted",
+                file.path
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the IDs of one kind in an archive are UUIDs and appear only once. IDs become file names, so anything else could write outside .data.
+fn check_archive_ids<'a>(kind: &str, ids: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if uuid::Uuid::parse_str(id).is_err() {
+            return Err(format!("Backup contains an invalid {} ID '{}'", kind, id));
+        }
+        if !seen.insert(id) {
+            return Err(format!(
+                "Backup contains the {} ID '{}' more than once",
+                kind, id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns `id` unchanged if it's free, or a fresh UUID (recorded in `remapped`) if it's already taken.
+fn remap_id(id: &str, taken: &HashSet<String>, remapped: &mut HashMap<String, String>) -> String {
+    if !taken.contains(id) {
+        return id.to_string();
+    }
+    let new_id = uuid::Uuid::new_v4().to_string();
+    remapped.insert(id.to_string(), new_id.clone());
+    new_id
+}
+
+/// Tauri command: Restores a workspace from a backup archive. Verifies all checksums and IDs before writing anything and remaps workspace, folder and chat IDs that already exist.
+#[tauri::command]
+pub async fn restore_workspace(path: String) -> Result<RestoreReport, String> {
+    let mut entries = read_archive(&path)?;
+    let manifest: BackupManifest = from_json_bytes(&entries, MANIFEST_PATH)?;
+    verify_manifest(&manifest, &entries)?;
+
+    // Only checksummed files may be restored
+    let listed: HashSet<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    entries.retain(|entry_path, _| listed.contains(entry_path.as_str()));
+
+    let mut workspace: WorkspaceMeta = from_json_bytes(&entries, WORKSPACE_PATH)?;
+    let mut folders: Vec<FolderMeta> = from_json_bytes(&entries, FOLDERS_PATH)?;
+    let chats: Vec<ChatMeta> = from_json_bytes(&entries, CHATS_INDEX_PATH)?;
+    check_archive_ids("workspace", [workspace.id.as_str()])?;
+    check_archive_ids("folder", folders.iter().map(|f| f.id.as_str()))?;
+    check_archive_ids("chat", chats.iter().map(|c| c.id.as_str()))?;
+    let mut chat_files: Vec<(ChatMeta, ChatData)> = Vec::with_capacity(chats.len());
+    for chat in chats {
+        let data: ChatData = from_json_bytes(&entries, &format!("chats/{}.json", chat.id))?;
+        chat_files.push((chat, data));
+    }
+    let document_index: Option<DocumentIndex> = if entries.contains_key(DOCUMENT_INDEX_PATH) {
+        Some(from_json_bytes(&entries, DOCUMENT_INDEX_PATH)?)
+    } else {
+        None
+    };
+
+    // Everything has been parsed and verified; now work out non-conflicting IDs
+    let mut workspaces_index = load_workspaces_index()?;
+    let mut folders_index = load_folders_index()?;
+    let chats_index = load_chats_index()?;
+
+    let mut remapped: HashMap<String, String> = HashMap::new();
+
+    let taken_workspaces: HashSet<String> = workspaces_index
+        .workspaces
+        .iter()
+        .map(|w| w.id.clone())
+        .collect();
+    workspace.id = remap_id(&workspace.id, &taken_workspaces, &mut remapped);
+
+    let taken_folders: HashSet<String> =
+        folders_index.folders.iter().map(|f| f.id.clone()).collect();
+    let folder_ids: HashMap<String, String> = folders
+        .iter()
+        .map(|f| (f.id.clone(), remap_id(&f.id, &taken_folders, &mut remapped)))
+        .collect();
+
+    let taken_chats: HashSet<String> = chats_index.chats.iter().map(|c| c.id.clone()).collect();
+    let chat_ids: HashMap<String, String> = chat_files
+        .iter()
+        .map(|(c, _)| (c.id.clone(), remap_id(&c.id, &taken_chats, &mut remapped)))
+        .collect();
+
+    for folder in &mut folders {
+        folder.id = folder_ids[&folder.id].clone();
+        folder.workspace_id = workspace.id.clone();
+        // A parent missing from the archive would point into another workspace
+        folder.parent_id = folder
+            .parent_id
+            .as_ref()
+            .and_then(|pid| folder_ids.get(pid).cloned());
+        folder.chat_ids = folder
+            .chat_ids
+            .iter()
+            .filter_map(|id| chat_ids.get(id).cloned())
+            .collect();
+    }
+    for (chat, _) in &mut chat_files {
+        chat.id = chat_ids[&chat.id].clone();
+        chat.file_location = format!(".data/chats/{}.json", chat.id);
+        chat.workspace_id = workspace.id.clone();
+        chat.folder_id = chat
+            .folder_id
+            .as_ref()
+            .and_then(|fid| folder_ids.get(fid).cloned());
+    }
+
+    let report = RestoreReport {
+        workspace: workspace.clone(),
+        folders_restored: folders.len(),
+        chats_restored: chat_files.len(),
+        remapped_ids: remapped.len(),
+    };
+
+    workspaces_index.workspaces.push(workspace.clone());
+    save_workspaces_index(&workspaces_index)?;
+    folders_index.folders.extend(folders);
+    save_folders_index(&folders_index)?;
+    insert_chats(chat_files)?;
+    if let Some(mut document_index) = document_index {
+        document_index.workspace_id = workspace.id;
+        save_document_index(&document_index)?;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+use super::chat_storage::{
+    get_chat_file_path, load_chat_data, load_chats_index, now_iso, remove_from_search_indexes,
+    save_chats_index, ChatMeta,
+};
+use crate::api::export::chat_export::{
+    write_exported_chats, ExportFormat, ExportScope, ExportedChat,
+};
+use crate::api::folders::folders_storage::{load_folders_index, save_folders_index};
+use crate::api::tags::tag_storage::{insert_tag, normalize_tag};
+use crate::api::trash::trash_storage::{add_chat_trash_items, stash_chat_file, undo_trash};
+
+/// Outcome of a bulk operation for one chat. `error` says why the chat was skipped.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkItemResult {
+    pub chat_id: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Per-chat results of a bulk operation, in the order the IDs were given.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkReport {
+    pub results: Vec<BulkItemResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl BulkReport {
+    /// Builds a report from per-chat results.
+    fn from_results(results: Vec<BulkItemResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.ok).count();
+        BulkReport {
+            failed: results.len() - succeeded,
+            succeeded,
+            results,
+        }
+    }
+}
+
+/// A successful per-chat result.
+fn item_ok(chat_id: &str) -> BulkItemResult {
+    BulkItemResult {
+        chat_id: chat_id.to_string(),
+        ok: true,
+        error: None,
+    }
+}
+
+/// A failed per-chat result.
+fn item_error(chat_id: &str, error: String) -> BulkItemResult {
+    BulkItemResult {
+        chat_id: chat_id.to_string(),
+        ok: false,
+        error: Some(error),
+    }
+}
+
+/// Returns the chat IDs once each, in the order given. Repeated IDs would otherwise be reported as not found the second time.
+fn unique_ids(chat_ids: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = vec![];
+    for id in chat_ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
+
+/// Tauri command: Moves many chats to the trash at once, each as its own trash item. Indexes are loaded and saved once. Called from frontend multi-select actions.
+#[tauri::command]
+pub async fn bulk_delete_chats(chat_ids: Vec<String>) -> Result<BulkReport, String> {
+    let mut index = load_chats_index()?;
+    let mut folders_index = load_folders_index()?;
+    let mut results = vec![];
+    let mut removed: Vec<ChatMeta> = vec![];
+
+    for chat_id in unique_ids(chat_ids) {
+        let Some(position) = index.chats.iter().position(|c| c.id == chat_id) else {
+            results.push(item_error(
+                &chat_id,
+                format!("Chat with id '{}' not found", chat_id),
+            ));
+            continue;
+        };
+        if let Err(e) = stash_chat_file(&chat_id) {
+            results.push(item_error(&chat_id, e));
+            continue;
+        }
+        let chat = index.chats.remove(position);
+        if let Some(ref fid) = chat.folder_id {
+            if let Some(folder) = folders_index.folders.iter_mut().find(|f| &f.id == fid) {
+                folder.chat_ids.retain(|id| id != &chat_id);
+            }
+        }
+        removed.push(chat);
+        results.push(item_ok(&chat_id));
+    }
+
+    if !removed.is_empty() {
+        let removed_ids: Vec<String> = removed.iter().map(|c| c.id.clone()).collect();
+        // The trash items are written before the chats leave the index, so a failure never loses them
+        let trash_ids = match add_chat_trash_items(removed) {
+            Ok(ids) => ids,
+            Err(e) => {
+                undo_trash(&[], &removed_ids);
+                return Err(e);
+            }
+        };
+        if let Err(e) = save_chats_index(&index) {
+            undo_trash(&trash_ids, &removed_ids);
+            return Err(e);
+        }
+        // A folder still listing a deleted chat is repaired by the integrity check
+        if let Err(e) = save_folders_index(&folders_index) {
+            eprintln!("Failed to remove deleted chats from their folders: {}", e);
+        }
+        remove_from_search_indexes(&removed_ids);
+    }
+
+    Ok(BulkReport::from_results(results))
+}
+
+/// Tauri command: Files many chats into a folder, or takes them out of their folders when `folder_id` is None. Chats from another workspace than the folder are skipped. Called from frontend multi-select actions.
+#[tauri::command]
+pub async fn bulk_move_chats(
+    chat_ids: Vec<String>,
+    folder_id: Option<String>,
+) -> Result<BulkReport, String> {
+    let mut index = load_chats_index()?;
+    let mut folders_index = load_folders_index()?;
+    let target_workspace = match folder_id {
+        Some(ref fid) => Some(
+            folders_index
+                .folders
+                .iter()
+                .find(|f| &f.id == fid)
+                .map(|f| f.workspace_id.clone())
+                .ok_or_else(|| format!("Folder with id '{}' not found", fid))?,
+        ),
+        None => None,
+    };
+
+    let now = now_iso();
+    let mut results = vec![];
+    for chat_id in unique_ids(chat_ids) {
+        let Some(chat) = index.chats.iter_mut().find(|c| c.id == chat_id) else {
+            results.push(item_error(
+                &chat_id,
+                format!("Chat with id '{}' not found", chat_id),
+            ));
+            continue;
+        };
+        if target_workspace
+            .as_ref()
+            .is_some_and(|ws| ws != &chat.workspace_id)
+        {
+            results.push(item_error(
+                &chat_id,
+                "The target folder belongs to a different workspace".to_string(),
+            ));
+            continue;
+        }
+
+        for folder in &mut folders_index.folders {
+            if chat.folder_id.as_ref() == Some(&folder.id) && folder_id.as_ref() != Some(&folder.id)
+            {
+                folder.chat_ids.retain(|id| id != &chat_id);
+                folder.last_updated_at = now.clone();
+            }
+            if folder_id.as_ref() == Some(&folder.id) && !folder.chat_ids.contains(&chat_id) {
+                folder.chat_ids.push(chat_id.clone());
+                folder.last_updated_at = now.clone();
+            }
+        }
+        chat.folder_id = folder_id.clone();
+        chat.last_updated_at = now.clone();
+        results.push(item_ok(&chat_id));
+    }
+
+    if results.iter().any(|r| r.ok) {
+        save_chats_index(&index)?;
+        save_folders_index(&folders_index)?;
+    }
+
+    Ok(BulkReport::from_results(results))
+}
+
+/// Tauri command: Adds and removes tags on many chats at once. Invalid tags fail the whole call before anything changes. Called from frontend multi-select actions.
+#[tauri::command]
+pub async fn bulk_tag_chats(
+    chat_ids: Vec<String>,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<BulkReport, String> {
+    let add: Vec<String> = add
+        .unwrap_or_default()
+        .iter()
+        .map(|t| normalize_tag(t))
+        .collect::<Result<_, _>>()?;
+    let remove: Vec<String> = remove
+        .unwrap_or_default()
+        .iter()
+        .map(|t| normalize_tag(t))
+        .collect::<Result<_, _>>()?;
+
+    let mut index = load_chats_index()?;
+    let mut results = vec![];
+    for chat_id in unique_ids(chat_ids) {
+        let Some(chat) = index.chats.iter_mut().find(|c| c.id == chat_id) else {
+            results.push(item_error(
+                &chat_id,
+                format!("Chat with id '{}' not found", chat_id),
+            ));
+            continue;
+        };
+        chat.tags.retain(|t| !remove.contains(t));
+        for tag in &add {
+            insert_tag(&mut chat.tags, tag.clone());
+        }
+        results.push(item_ok(&chat_id));
+    }
+
+    if results.iter().any(|r| r.ok) {
+        save_chats_index(&index)?;
+    }
+
+    Ok(BulkReport::from_results(results))
+}
+
+/// Loads a chat's messages for a bulk export. Unlike load_chat_data, a missing chat file is an error.
+fn load_chat_for_export(chat: &ChatMeta) -> Result<ExportedChat, String> {
+    if !get_chat_file_path(&chat.id)?.exists() {
+        return Err(format!("Chat file of '{}' is missing", chat.chat_title));
+    }
+    Ok(ExportedChat {
+        meta: chat.clone(),
+        messages: load_chat_data(&chat.id)?.messages,
+    })
+}
+
+/// Tauri command: Exports many chats into one file at the given path. Unknown chats and chats whose file can't be read are reported and left out. Called from frontend multi-select actions.
+#[tauri::command]
+pub async fn bulk_export_chats(
+    chat_ids: Vec<String>,
+    format: ExportFormat,
+    path: String,
+) -> Result<BulkReport, String> {
+    let index = load_chats_index()?;
+    let mut results = vec![];
+    let mut chats: Vec<ExportedChat> = vec![];
+    for chat_id in unique_ids(chat_ids) {
+        let loaded = index
+            .chats
+            .iter()
+            .find(|c| c.id == chat_id)
+            .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))
+            .and_then(load_chat_for_export);
+        match loaded {
+            Ok(chat) => {
+                chats.push(chat);
+                results.push(item_ok(&chat_id));
+            }
+            Err(e) => results.push(item_error(&chat_id, e)),
+        }
+    }
+    if chats.is_empty() {
+        return Err("None of the selected chats could be exported".to_string());
+    }
+
+    let scope = ExportScope {
+        kind: "selection",
+        name: format!("{} chats", chats.len()),
+    };
+    write_exported_chats(chats, format, Some(&scope), &path)?;
+
+    Ok(BulkReport::from_results(results))
+}
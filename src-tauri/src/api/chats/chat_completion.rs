@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+use super::think_parser::strip_think_blocks;
+
+/// A single message sent to Ollama for a one-shot (non-streaming) completion.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Request body for a non-streaming call to Ollama's /api/chat endpoint.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaCompletionRequest {
+    model: String,
+    messages: Vec<CompletionMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<serde_json::Value>,
+}
+
+/// The parts of a non-streaming /api/chat response we use.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaCompletionResponse {
+    message: CompletionMessage,
+    prompt_eval_count: Option<u64>,
+}
+
+/// Builds a CompletionMessage from a role and content.
+pub fn completion_message(role: &str, content: impl Into<String>) -> CompletionMessage {
+    CompletionMessage {
+        role: role.to_string(),
+        content: content.into(),
+    }
+}
+
+/// Sends a non-streaming request to Ollama's /api/chat and parses the response. Shared by the completion helpers below.
+async fn post_completion(
+    model: &str,
+    messages: Vec<CompletionMessage>,
+    format: Option<serde_json::Value>,
+    options: Option<serde_json::Value>,
+) -> Result<OllamaCompletionResponse, String> {
+    let client = reqwest::Client::new();
+
+    let body = OllamaCompletionRequest {
+        model: model.to_string(),
+        messages,
+        stream: false,
+        format,
+        options,
+    };
+
+    let response = client
+        .post("http://localhost:11434/api/chat")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_connect() {
+                "Could not connect to Ollama. Make sure Ollama is running on http://localhost:11434"
+                    .to_string()
+            } else if e.is_timeout() {
+                format!(
+                    "Request to Ollama timed out while running model '{}'",
+                    model
+                )
+            } else {
+                format!("Network error while running model '{}': {}", model, e)
+            }
+        })?;
+
+    let status = response.status();
+
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        let ollama_msg = serde_json::from_str::<serde_json::Value>(&error_body)
+            .ok()
+            .and_then(|v| v["error"].as_str().map(String::from))
+            .unwrap_or(error_body);
+
+        return Err(match status.as_u16() {
+            404 => format!("Model '{}' not found. Pull it first", model),
+            _ => format!(
+                "Ollama returned HTTP {} while running model '{}': {}",
+                status, model, ollama_msg
+            ),
+        });
+    }
+
+    response
+        .json::<OllamaCompletionResponse>()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to parse the completion response for model '{}' from Ollama: {}",
+                model, e
+            )
+        })
+}
+
+/// Runs a one-shot chat completion against Ollama's /api/chat and returns the full reply without inline reasoning. Used for background tasks such as titling and summarization.
+pub async fn complete_chat(
+    model: &str,
+    messages: Vec<CompletionMessage>,
+    options: Option<serde_json::Value>,
+) -> Result<String, String> {
+    let response = post_completion(model, messages, None, options).await?;
+    Ok(strip_think_blocks(&response.message.content))
+}
+
+/// Runs a one-shot chat completion constrained by Ollama's `format` ("json" or a JSON Schema), without inline reasoning. Used to retry structured replies that failed validation.
+pub async fn complete_chat_with_format(
+    model: &str,
+    messages: Vec<CompletionMessage>,
+    format: serde_json::Value,
+    options: Option<serde_json::Value>,
+) -> Result<String, String> {
+    let response = post_completion(model, messages, Some(format), options).await?;
+    Ok(strip_think_blocks(&response.message.content))
+}
+
+/// Asks Ollama how many tokens the given messages occupy by running a dry completion that generates a single token. Used by the context manager when exact counting is enabled.
+pub async fn count_prompt_tokens(
+    model: &str,
+    messages: Vec<CompletionMessage>,
+    num_ctx: u32,
+) -> Result<u64, String> {
+    let options = serde_json::json!({ "num_ctx": num_ctx, "num_predict": 1 });
+    let response = post_completion(model, messages, None, Some(options)).await?;
+    response.prompt_eval_count.ok_or_else(|| {
+        format!(
+            "Ollama did not report a prompt token count for model '{}'",
+            model
+        )
+    })
+}
@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::chat_storage::{load_chats_index, ChatMeta};
+
+/// Number of chats returned per page when no limit is given.
+const DEFAULT_PAGE_SIZE: usize = 50;
+/// Largest page list_chats will return.
+const MAX_PAGE_SIZE: usize = 500;
+
+/// The field chats are ordered by in list_chats.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatSortKey {
+    Created,
+    #[default]
+    Updated,
+    Title,
+    Model,
+}
+
+/// Sort direction for list_chats.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Which chats list_chats returns and in what order. Missing fields list the newest-updated chats of every workspace, pinned first, without archived ones.
+/// `cursor` is the `next_cursor` of the previous page and must be used with the same filters and sort.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ChatListQuery {
+    pub workspace_id: Option<String>,
+    pub folder_id: Option<String>,
+    pub include_archived: bool,
+    pub sort: ChatSortKey,
+    pub direction: SortDirection,
+    pub pinned_first: Option<bool>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// One page of chats. `total` counts every chat matching the filters across all pages; `next_cursor` is None on the last page.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatPage {
+    pub chats: Vec<ChatMeta>,
+    pub next_cursor: Option<String>,
+    pub total: usize,
+}
+
+/// Where a chat sits in the requested order. Serialized as the page cursor, so paging stays stable when chats are added or removed in between.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SortPosition {
+    pinned: bool,
+    value: String,
+    id: String,
+}
+
+/// Returns the value a chat is sorted by. Timestamps are normalized to UTC so they compare correctly as text.
+fn sort_value(chat: &ChatMeta, key: ChatSortKey) -> String {
+    let timestamp = |value: &str| {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .map(|dt| {
+                dt.with_timezone(&chrono::Utc)
+                    .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
+            })
+            .unwrap_or_default()
+    };
+    match key {
+        ChatSortKey::Created => timestamp(&chat.created_at),
+        ChatSortKey::Updated => timestamp(&chat.last_updated_at),
+        ChatSortKey::Title => chat.chat_title.to_lowercase(),
+        ChatSortKey::Model => chat.model_used.to_lowercase(),
+    }
+}
+
+/// Compares two positions in the requested order. Pinned chats come first when `pinned_first` is set; the chat ID breaks ties.
+fn compare_positions(
+    a: &SortPosition,
+    b: &SortPosition,
+    direction: SortDirection,
+    pinned_first: bool,
+) -> Ordering {
+    let pinned = if pinned_first {
+        b.pinned.cmp(&a.pinned)
+    } else {
+        Ordering::Equal
+    };
+    let rest = (a.value.as_str(), a.id.as_str()).cmp(&(b.value.as_str(), b.id.as_str()));
+    pinned.then(match direction {
+        SortDirection::Asc => rest,
+        SortDirection::Desc => rest.reverse(),
+    })
+}
+
+/// Returns true if a chat passes the workspace, folder and archive filters.
+fn matches_filters(
+    chat: &ChatMeta,
+    workspace_id: Option<&str>,
+    folder_id: Option<&str>,
+    include_archived: bool,
+) -> bool {
+    workspace_id.is_none_or(|ws| chat.workspace_id == ws)
+        && folder_id.is_none_or(|fid| chat.folder_id.as_deref() == Some(fid))
+        && (include_archived || !chat.archived)
+}
+
+/// Filters, sorts and pages a list of chats as the query asks. Used by list_chats on the chats index.
+fn page_chats(chats: Vec<ChatMeta>, query: &ChatListQuery) -> Result<ChatPage, String> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let pinned_first = query.pinned_first.unwrap_or(true);
+    let cursor: Option<SortPosition> = match query.cursor {
+        Some(ref cursor) => {
+            Some(serde_json::from_str(cursor).map_err(|_| "Invalid page cursor".to_string())?)
+        }
+        None => None,
+    };
+
+    let mut entries: Vec<(SortPosition, ChatMeta)> = chats
+        .into_iter()
+        .filter(|c| {
+            matches_filters(
+                c,
+                query.workspace_id.as_deref(),
+                query.folder_id.as_deref(),
+                query.include_archived,
+            )
+        })
+        .map(|c| {
+            let position = SortPosition {
+                pinned: c.pinned,
+                value: sort_value(&c, query.sort),
+                id: c.id.clone(),
+            };
+            (position, c)
+        })
+        .collect();
+    let total = entries.len();
+
+    entries.sort_by(|(a, _), (b, _)| compare_positions(a, b, query.direction, pinned_first));
+    if let Some(ref cursor) = cursor {
+        entries.retain(|(position, _)| {
+            compare_positions(position, cursor, query.direction, pinned_first) == Ordering::Greater
+        });
+    }
+
+    let has_more = entries.len() > limit;
+    entries.truncate(limit);
+    let next_cursor = match entries.last() {
+        Some((position, _)) if has_more => Some(
+            serde_json::to_string(position)
+                .map_err(|e| format!("Failed to serialize page cursor: {}", e))?,
+        ),
+        _ => None,
+    };
+
+    Ok(ChatPage {
+        chats: entries.into_iter().map(|(_, chat)| chat).collect(),
+        next_cursor,
+        total,
+    })
+}
+
+/// Tauri command: Returns one page of chats, filtered and sorted as requested. Called from frontend sidebar to load chats incrementally.
+#[tauri::command]
+pub async fn list_chats(query: Option<ChatListQuery>) -> Result<ChatPage, String> {
+    page_chats(load_chats_index()?.chats, &query.unwrap_or_default())
+}
+
+/// Tauri command: Returns how many chats match the workspace and archive filters without sending them. Called from frontend to size the sidebar list.
+#[tauri::command]
+pub async fn count_chats(
+    workspace_id: Option<String>,
+    include_archived: Option<bool>,
+) -> Result<usize, String> {
+    let include_archived = include_archived.unwrap_or(false);
+    Ok(load_chats_index()?
+        .chats
+        .iter()
+        .filter(|c| matches_filters(c, workspace_id.as_deref(), None, include_archived))
+        .count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat(id: &str, updated: &str, pinned: bool) -> ChatMeta {
+        ChatMeta {
+            id: id.to_string(),
+            chat_title: format!("Chat {}", id),
+            file_location: format!("{}.json", id),
+            model_used: "llama3".to_string(),
+            models_used: vec![],
+            workspace_id: "ws".to_string(),
+            folder_id: None,
+            created_at: updated.to_string(),
+            last_updated_at: updated.to_string(),
+            rag_enabled: false,
+            custom_title: false,
+            summary: None,
+            preset_id: None,
+            tags: vec![],
+            pinned,
+            starred: false,
+            archived: false,
+        }
+    }
+
+    fn position(pinned: bool, value: &str, id: &str) -> SortPosition {
+        SortPosition {
+            pinned,
+            value: value.to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    fn ids(page: &ChatPage) -> Vec<&str> {
+        page.chats.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    /// Follows next_cursor until the last page and returns the IDs in the order they were listed.
+    fn all_pages(chats: &[ChatMeta], mut query: ChatListQuery) -> Vec<String> {
+        let mut listed = vec![];
+        loop {
+            let page = page_chats(chats.to_vec(), &query).unwrap();
+            listed.extend(page.chats.iter().map(|c| c.id.clone()));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => return listed,
+            }
+        }
+    }
+
+    /// Five chats: "b" and "d" are pinned; "a" and "b" share a timestamp, as do "c" and "d".
+    fn sample() -> Vec<ChatMeta> {
+        vec![
+            chat("a", "2024-01-01T10:00:00Z", false),
+            chat("b", "2024-01-01T10:00:00Z", true),
+            chat("c", "2024-01-02T10:00:00Z", false),
+            chat("d", "2024-01-02T10:00:00Z", true),
+            chat("e", "2024-01-03T10:00:00Z", false),
+        ]
+    }
+
+    #[test]
+    fn compare_positions_puts_pinned_chats_first_in_both_directions() {
+        let pinned = position(true, "2024-01-01", "a");
+        let newer = position(false, "2024-06-01", "b");
+        for direction in [SortDirection::Asc, SortDirection::Desc] {
+            assert_eq!(
+                compare_positions(&pinned, &newer, direction, true),
+                Ordering::Less
+            );
+        }
+        assert_eq!(
+            compare_positions(&pinned, &newer, SortDirection::Desc, false),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn compare_positions_breaks_ties_on_the_id() {
+        let a = position(false, "same", "a");
+        let b = position(false, "same", "b");
+        assert_eq!(
+            compare_positions(&a, &b, SortDirection::Asc, true),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_positions(&a, &b, SortDirection::Desc, true),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_positions(&a, &a, SortDirection::Desc, true),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn page_chats_sorts_pinned_first_and_ties_by_id() {
+        let page = page_chats(sample(), &ChatListQuery::default()).unwrap();
+        assert_eq!(ids(&page), ["d", "b", "e", "c", "a"]);
+        assert_eq!(page.total, 5);
+        assert!(page.next_cursor.is_none());
+
+        let query = ChatListQuery {
+            direction: SortDirection::Asc,
+            pinned_first: Some(false),
+            ..Default::default()
+        };
+        let page = page_chats(sample(), &query).unwrap();
+        assert_eq!(ids(&page), ["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn cursor_paging_lists_every_chat_once() {
+        let full = page_chats(sample(), &ChatListQuery::default()).unwrap();
+        for limit in 1..=5 {
+            let query = ChatListQuery {
+                limit: Some(limit),
+                ..Default::default()
+            };
+            assert_eq!(all_pages(&sample(), query), ids(&full));
+        }
+    }
+
+    #[test]
+    fn cursor_paging_applies_filters() {
+        let mut chats = sample();
+        chats[2].archived = true;
+        chats[4].workspace_id = "other".to_string();
+        let query = ChatListQuery {
+            workspace_id: Some("ws".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(all_pages(&chats, query.clone()), ["d", "b", "a"]);
+        assert_eq!(page_chats(chats, &query).unwrap().total, 3);
+    }
+
+    #[test]
+    fn cursor_paging_survives_chats_deleted_between_pages() {
+        let mut chats = sample();
+        let query = ChatListQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first = page_chats(chats.clone(), &query).unwrap();
+        assert_eq!(ids(&first), ["d", "b"]);
+
+        // Delete the cursor's own chat and the next one in line
+        chats.retain(|c| c.id != "b" && c.id != "e");
+        let second = page_chats(
+            chats,
+            &ChatListQuery {
+                cursor: first.next_cursor,
+                ..query
+            },
+        )
+        .unwrap();
+        assert_eq!(ids(&second), ["c", "a"]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn page_chats_rejects_an_invalid_cursor() {
+        let query = ChatListQuery {
+            cursor: Some("not a cursor".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            page_chats(sample(), &query).unwrap_err(),
+            "Invalid page cursor"
+        );
+    }
+}
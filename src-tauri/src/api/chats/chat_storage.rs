@@ -2,31 +2,99 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-/// Represents a single message in a chat conversation (role: user/assistant, content: message text).
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::api::folders::folders_storage::{check_move_target, transfer_chat_between_folders};
+use crate::api::search::search_index::{index_chat, index_chats, remove_chats_from_search_index};
+use crate::api::search::semantic_index::{
+    remove_chats_from_semantic_index, schedule_chat_embedding, schedule_chats_embedding,
+};
+use crate::api::tags::tag_storage::{has_all_tags, insert_tag, normalize_tag};
+use crate::api::trash::trash_storage::{
+    add_trash_item, stash_chat_file, undo_trash, TrashItemKind,
+};
+
+/// Represents a single message in a chat conversation (role: user/assistant, content: message text, creation time, document chunks cited when RAG was used, the parsed value of a structured JSON reply, the model's reasoning trace, and the model that wrote an assistant reply).
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<MessageCitation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
-/// Contains the list of messages for a chat. Stored in individual chat JSON files.
+/// A workspace document chunk that was supplied as context for an assistant reply. `cited` is true when the reply referenced it by number.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageCitation {
+    pub chunk_id: String,
+    pub source_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub cited: bool,
+}
+
+/// Contains the list of messages for a chat, plus the pinned summary of older turns when the context manager has summarized them. Stored in individual chat JSON files.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ChatData {
     pub messages: Vec<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_summary: Option<ContextSummary>,
+}
+
+/// A model-written summary of the first `covers_messages` messages of a chat. Sent in place of those messages once the history no longer fits the context window.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContextSummary {
+    pub content: String,
+    pub covers_messages: usize,
+    pub created_at: String,
 }
 
-/// Metadata for a chat stored in the chats index. Contains id, title, model, workspace, folder, and timestamps.
+/// A digest of a whole chat produced by summarize_chat, stored on the chat's metadata.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatSummary {
+    pub content: String,
+    pub model: String,
+    pub message_count: usize,
+    pub created_at: String,
+}
+
+/// Metadata for a chat stored in the chats index. Contains id, title, model, workspace, folder, and timestamps. `model_used` is the model the next message goes to; `models_used` lists every model that has replied, in order of first use. `custom_title` is set once the user renames the chat so automatic titling leaves it alone. `preset_id` is the preset the chat was started from. `tags` are normalized and kept sorted. `pinned` chats are listed first, `starred` ones are marked as favourites, and `archived` ones are hidden from listings unless asked for.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatMeta {
     pub id: String,
     pub chat_title: String,
     pub file_location: String,
     pub model_used: String,
+    #[serde(default)]
+    pub models_used: Vec<String>,
     pub workspace_id: String,
     #[serde(default)]
     pub folder_id: Option<String>,
     pub created_at: String,
     pub last_updated_at: String,
+    #[serde(default)]
+    pub rag_enabled: bool,
+    #[serde(default)]
+    pub custom_title: bool,
+    #[serde(default)]
+    pub summary: Option<ChatSummary>,
+    #[serde(default)]
+    pub preset_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub archived: bool,
 }
 
 /// The root structure for the chats index file (chats_index.json). Contains list of all ChatMeta entries.
@@ -56,12 +124,29 @@ fn get_index_path() -> Result<PathBuf, String> {
     Ok(data_dir.join("chats_index.json"))
 }
 
-/// Returns the file path for a specific chat's JSON data file. Used internally when loading/saving chat data.
-fn get_chat_file_path(chat_id: &str) -> Result<PathBuf, String> {
+/// Returns the file path for a specific chat's JSON data file. Used when loading/saving chat data and when moving chats to the trash.
+pub fn get_chat_file_path(chat_id: &str) -> Result<PathBuf, String> {
     let data_dir = get_data_dir()?;
     Ok(data_dir.join("chats").join(format!("{}.json", chat_id)))
 }
 
+/// Returns the IDs of all chat data files in .data/chats, whether or not the index knows them. Used by the data integrity check.
+pub fn list_chat_file_ids() -> Result<Vec<String>, String> {
+    let chats_dir = get_data_dir()?.join("chats");
+    let entries = fs::read_dir(&chats_dir)
+        .map_err(|e| format!("Failed to read .data/chats directory: {}", e))?;
+    let mut ids = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                ids.push(stem.to_string());
+            }
+        }
+    }
+    Ok(ids)
+}
+
 /// Loads the chats index from chats_index.json, creating it with an empty list if it doesn't exist. Used by Tauri commands to get all chat metadata.
 pub fn load_chats_index() -> Result<ChatsIndex, String> {
     let index_path = get_index_path()?;
@@ -87,40 +172,55 @@ pub fn save_chats_index(index: &ChatsIndex) -> Result<(), String> {
 pub fn load_chat_data(chat_id: &str) -> Result<ChatData, String> {
     let chat_path = get_chat_file_path(chat_id)?;
     if !chat_path.exists() {
-        return Ok(ChatData { messages: vec![] });
+        return Ok(ChatData::default());
     }
     let content =
         fs::read_to_string(&chat_path).map_err(|e| format!("Failed to read chat data: {}", e))?;
     serde_json::from_str(&content).map_err(|e| format!("Failed to parse chat data: {}", e))
 }
 
-/// Saves chat messages to a specific chat's JSON file. Used by send_chat_message when storing user/assistant messages.
-pub fn save_chat_data(chat_id: &str, data: &ChatData) -> Result<(), String> {
+/// Writes chat messages to a specific chat's JSON file without touching the search or embedding indexes.
+fn write_chat_file(chat_id: &str, data: &ChatData) -> Result<(), String> {
     let chat_path = get_chat_file_path(chat_id)?;
     let content = serde_json::to_string_pretty(data)
         .map_err(|e| format!("Failed to serialize chat data: {}", e))?;
     fs::write(&chat_path, content).map_err(|e| format!("Failed to write chat data: {}", e))
 }
 
+/// Saves chat messages to a specific chat's JSON file, refreshes its search index entries and queues embedding of new messages. Only the file write can fail the save. Used by send_chat_message when storing user/assistant messages.
+pub fn save_chat_data(chat_id: &str, data: &ChatData) -> Result<(), String> {
+    write_chat_file(chat_id, data)?;
+    // The chat is already saved; a stale search entry is fixed by rebuild_search_index
+    if let Err(e) = index_chat(chat_id, data) {
+        eprintln!(
+            "Failed to update search index for chat '{}': {}",
+            chat_id, e
+        );
+    }
+    schedule_chat_embedding(chat_id);
+    Ok(())
+}
+
 /// Returns the current UTC time as an ISO 8601 RFC3339 string. Used for setting timestamps on chat metadata.
-fn now_iso() -> String {
+pub fn now_iso() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
-/// Creates a new chat with the given model, first message, workspace ID, and optional folder ID. Used by send_chat_message when starting a new conversation.
+/// Creates a new chat with the given model, first message, workspace ID, and optional folder and preset IDs. Used by send_chat_message when starting a new conversation.
 pub fn create_new_chat(
     model: &str,
     first_message: &str,
     workspace_id: &str,
     folder_id: Option<String>,
+    preset_id: Option<String>,
 ) -> Result<ChatMeta, String> {
     let id = uuid::Uuid::new_v4().to_string();
     let now = now_iso();
     let file_location = format!(".data/chats/{}.json", id);
 
-    // Generate a title from the first message (truncate to 50 chars)
-    let chat_title = if first_message.len() > 50 {
-        format!("{}...", &first_message[..50])
+    // Generate a title from the first message (truncate to 50 chars, on a character boundary)
+    let chat_title = if first_message.chars().count() > 50 {
+        format!("{}...", first_message.chars().take(50).collect::<String>())
     } else {
         first_message.to_string()
     };
@@ -130,14 +230,23 @@ pub fn create_new_chat(
         chat_title,
         file_location,
         model_used: model.to_string(),
+        models_used: vec![],
         workspace_id: workspace_id.to_string(),
         folder_id,
         created_at: now.clone(),
         last_updated_at: now,
+        rag_enabled: false,
+        custom_title: false,
+        summary: None,
+        preset_id,
+        tags: vec![],
+        pinned: false,
+        starred: false,
+        archived: false,
     };
 
     // Save initial empty chat data
-    let chat_data = ChatData { messages: vec![] };
+    let chat_data = ChatData::default();
     save_chat_data(&id, &chat_data)?;
 
     // Add to index
@@ -148,16 +257,102 @@ pub fn create_new_chat(
     Ok(meta)
 }
 
-/// Updates the last_updated_at timestamp for a chat to the current time. Used by send_chat_message after receiving an assistant response.
-pub fn update_chat_timestamp(chat_id: &str) -> Result<(), String> {
+/// Returns the metadata of a single chat from the index. Used by send_chat_message to read per-chat settings such as RAG.
+pub fn get_chat_meta(chat_id: &str) -> Result<ChatMeta, String> {
+    load_chats_index()?
+        .chats
+        .into_iter()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))
+}
+
+/// Enables or disables retrieval-augmented generation for a chat. Used by set_chat_rag_enabled and by send_chat_message when the flag is passed.
+pub fn set_chat_rag(chat_id: &str, enabled: bool) -> Result<(), String> {
     let mut index = load_chats_index()?;
-    let now = now_iso();
-    if let Some(meta) = index.chats.iter_mut().find(|c| c.id == chat_id) {
-        meta.last_updated_at = now;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    chat.rag_enabled = enabled;
+    save_chats_index(&index)
+}
+
+/// Replaces a chat's title with a generated one unless the user has renamed it in the meantime. Returns whether the title was changed. Used by automatic titling.
+pub fn set_generated_title(chat_id: &str, title: &str) -> Result<bool, String> {
+    let mut index = load_chats_index()?;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    if chat.custom_title {
+        return Ok(false);
     }
+    chat.chat_title = title.to_string();
+    save_chats_index(&index)?;
+    Ok(true)
+}
+
+/// Stores a generated summary on a chat's metadata. Used by summarize_chat.
+pub fn set_chat_summary(chat_id: &str, summary: ChatSummary) -> Result<(), String> {
+    let mut index = load_chats_index()?;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    chat.summary = Some(summary);
     save_chats_index(&index)
 }
 
+/// Writes several complete chats at once and appends their metadata to the index with a single save, then indexes them for search in one pass and queues one embedding job. Used by import to keep original IDs, titles and timestamps.
+pub fn insert_chats(chats: Vec<(ChatMeta, ChatData)>) -> Result<(), String> {
+    let mut index = load_chats_index()?;
+    for (meta, data) in &chats {
+        write_chat_file(&meta.id, data)?;
+    }
+    // One index update and one embedding job for the whole batch instead of one per chat
+    let entries: Vec<(&str, &ChatData)> = chats
+        .iter()
+        .map(|(meta, data)| (meta.id.as_str(), data))
+        .collect();
+    if let Err(e) = index_chats(&entries) {
+        eprintln!("Failed to update search index for inserted chats: {}", e);
+    }
+    let chat_ids: Vec<String> = chats.iter().map(|(meta, _)| meta.id.clone()).collect();
+
+    index.chats.extend(chats.into_iter().map(|(meta, _)| meta));
+    save_chats_index(&index)?;
+    schedule_chats_embedding(chat_ids);
+    Ok(())
+}
+
+/// Records that `model` wrote the latest reply in a chat: makes it the chat's current model, adds it to models_used and bumps last_updated_at. Used by send_chat_message after receiving an assistant response.
+pub fn record_reply_model(chat_id: &str, model: &str) -> Result<(), String> {
+    let mut index = load_chats_index()?;
+    let now = now_iso();
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    add_model_used(chat, model);
+    chat.model_used = model.to_string();
+    chat.last_updated_at = now;
+    save_chats_index(&index)
+}
+
+/// Adds a model to a chat's models_used list if it is not there yet. Chats saved before the list existed are seeded with their original model first.
+fn add_model_used(chat: &mut ChatMeta, model: &str) {
+    if chat.models_used.is_empty() && !chat.model_used.is_empty() && chat.model_used != model {
+        chat.models_used.push(chat.model_used.clone());
+    }
+    if !chat.models_used.iter().any(|m| m == model) {
+        chat.models_used.push(model.to_string());
+    }
+}
+
 /// Sets the folder ID for a chat, moving it into or out of a folder. Used when organizing chats into folders via UI.
 pub fn set_chat_folder(chat_id: &str, folder_id: Option<String>) -> Result<(), String> {
     let mut index = load_chats_index()?;
@@ -174,28 +369,21 @@ pub fn remove_chat_from_folder(chat_id: &str) -> Result<(), String> {
     set_chat_folder(chat_id, None)
 }
 
-/// Deletes all chats belonging to a workspace and removes them from the index. Called when a workspace is deleted.
-pub fn delete_chats_for_workspace(workspace_id: &str) -> Result<(), String> {
-    let mut index = load_chats_index()?;
-
-    // Collect chat ids to delete their files
-    let to_delete: Vec<String> = index
-        .chats
-        .iter()
-        .filter(|c| c.workspace_id == workspace_id)
-        .map(|c| c.id.clone())
-        .collect();
-
-    // Remove chat files
-    for chat_id in &to_delete {
-        if let Ok(path) = get_chat_file_path(chat_id) {
-            let _ = fs::remove_file(path);
-        }
+/// Removes deleted chats from the full-text and semantic indexes. Failures are only logged: the chats are already gone, and search results skip chats missing from the index.
+/// rebuild_search_index and rebuild_semantic_index clean up any leftovers.
+pub fn remove_from_search_indexes(chat_ids: &[String]) {
+    if let Err(e) = remove_chats_from_search_index(chat_ids) {
+        eprintln!(
+            "Failed to remove deleted chats from the search index: {}",
+            e
+        );
+    }
+    if let Err(e) = remove_chats_from_semantic_index(chat_ids) {
+        eprintln!(
+            "Failed to remove deleted chats from the semantic index: {}",
+            e
+        );
     }
-
-    // Remove from index
-    index.chats.retain(|c| c.workspace_id != workspace_id);
-    save_chats_index(&index)
 }
 
 /// Tauri command: Returns all chats from the index. Called from frontend to display all chats in sidebar.
@@ -205,15 +393,30 @@ pub async fn get_all_chats() -> Result<Vec<ChatMeta>, String> {
     Ok(index.chats)
 }
 
-/// Tauri command: Returns all chats for a specific workspace. Called from frontend when filtering chats by workspace.
+/// Sorts chats for listing: pinned chats first, then the most recently updated.
+pub fn sort_chats(chats: &mut [ChatMeta]) {
+    chats.sort_by_cached_key(|c| {
+        (
+            std::cmp::Reverse(c.pinned),
+            std::cmp::Reverse(chrono::DateTime::parse_from_rfc3339(&c.last_updated_at).ok()),
+        )
+    });
+}
+
+/// Tauri command: Returns the chats of a workspace, pinned first and then most recently updated. Archived chats are left out unless `include_archived` is true. Called from frontend when filtering chats by workspace.
 #[tauri::command]
-pub async fn get_chats_for_workspace(workspace_id: String) -> Result<Vec<ChatMeta>, String> {
+pub async fn get_chats_for_workspace(
+    workspace_id: String,
+    include_archived: Option<bool>,
+) -> Result<Vec<ChatMeta>, String> {
+    let include_archived = include_archived.unwrap_or(false);
     let index = load_chats_index()?;
-    let filtered: Vec<ChatMeta> = index
+    let mut filtered: Vec<ChatMeta> = index
         .chats
         .into_iter()
-        .filter(|c| c.workspace_id == workspace_id)
+        .filter(|c| c.workspace_id == workspace_id && (include_archived || !c.archived))
         .collect();
+    sort_chats(&mut filtered);
     Ok(filtered)
 }
 
@@ -242,67 +445,266 @@ pub async fn rename_chat(chat_id: String, new_title: String) -> Result<(), Strin
         .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
 
     chat.chat_title = trimmed.to_string();
+    chat.custom_title = true;
     chat.last_updated_at = now;
 
     save_chats_index(&index)
 }
 
-/// Tauri command: Deletes a chat and its data file. Called from frontend when user deletes a chat.
+/// Tauri command: Switches the model a chat sends its next messages to. Earlier replies keep the model that wrote them. Called from frontend model picker in an open chat.
 #[tauri::command]
-pub async fn delete_chat(chat_id: String) -> Result<(), String> {
+pub async fn set_chat_model(chat_id: String, model: String) -> Result<ChatMeta, String> {
+    let trimmed = model.trim();
+    if trimmed.is_empty() {
+        return Err("Model cannot be empty".to_string());
+    }
+
     let mut index = load_chats_index()?;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    // Chats saved before models_used existed only know their original model, so keep it before switching away
+    if chat.models_used.is_empty()
+        && load_chat_data(&chat_id)?
+            .messages
+            .iter()
+            .any(|m| m.role == "assistant")
+    {
+        chat.models_used.push(chat.model_used.clone());
+    }
+    chat.model_used = trimmed.to_string();
+    let meta = chat.clone();
 
-    let position = index
+    save_chats_index(&index)?;
+    Ok(meta)
+}
+
+/// Tauri command: Turns retrieval over the workspace's documents on or off for a chat. Called from frontend chat settings toggle.
+#[tauri::command]
+pub async fn set_chat_rag_enabled(chat_id: String, enabled: bool) -> Result<(), String> {
+    set_chat_rag(&chat_id, enabled)
+}
+
+/// Tauri command: Adds a tag to a chat and returns the chat's tags. Called from frontend chat menu.
+#[tauri::command]
+pub async fn add_chat_tag(chat_id: String, tag: String) -> Result<Vec<String>, String> {
+    let tag = normalize_tag(&tag)?;
+    let mut index = load_chats_index()?;
+    let chat = index
         .chats
-        .iter()
-        .position(|c| c.id == chat_id)
+        .iter_mut()
+        .find(|c| c.id == chat_id)
         .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
 
-    let chat = &index.chats[position];
+    let added = insert_tag(&mut chat.tags, tag);
+    let tags = chat.tags.clone();
+    if added {
+        save_chats_index(&index)?;
+    }
+    Ok(tags)
+}
 
-    // If the chat belongs to a folder, remove it from the folder's chat_ids list
-    if let Some(ref fid) = chat.folder_id {
-        let _ = remove_chat_from_folder_list(fid, &chat_id);
+/// Tauri command: Removes a tag from a chat and returns the chat's tags. Removing a tag the chat doesn't have is not an error. Called from frontend chat menu.
+#[tauri::command]
+pub async fn remove_chat_tag(chat_id: String, tag: String) -> Result<Vec<String>, String> {
+    let tag = normalize_tag(&tag)?;
+    let mut index = load_chats_index()?;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+
+    let before = chat.tags.len();
+    chat.tags.retain(|t| t != &tag);
+    let tags = chat.tags.clone();
+    if tags.len() != before {
+        save_chats_index(&index)?;
     }
+    Ok(tags)
+}
+
+/// Tauri command: Moves a chat to another workspace, into `folder_id` there or unfiled. Its old folder lets go of it. Called from frontend chat menu.
+#[tauri::command]
+pub async fn move_chat_to_workspace(
+    chat_id: String,
+    workspace_id: String,
+    folder_id: Option<String>,
+) -> Result<ChatMeta, String> {
+    check_move_target(&workspace_id, folder_id.as_deref())?;
+
+    let mut index = load_chats_index()?;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    let old_folder = chat.folder_id.clone();
+    chat.workspace_id = workspace_id;
+    chat.folder_id = folder_id;
+    chat.last_updated_at = now_iso();
+    let meta = chat.clone();
 
-    index.chats.remove(position);
+    transfer_chat_between_folders(&chat_id, old_folder.as_deref(), meta.folder_id.as_deref())?;
     save_chats_index(&index)?;
+    Ok(meta)
+}
 
-    // Delete the chat data file
-    if let Ok(path) = get_chat_file_path(&chat_id) {
-        let _ = fs::remove_file(path);
+/// Tauri command: Copies a chat with all its messages under a new ID. The copy goes to `workspace_id` and `folder_id` when given, else next to the original. Called from frontend chat menu.
+#[tauri::command]
+pub async fn duplicate_chat(
+    chat_id: String,
+    workspace_id: Option<String>,
+    folder_id: Option<String>,
+) -> Result<ChatMeta, String> {
+    let source = get_chat_meta(&chat_id)?;
+    let workspace_id = workspace_id.unwrap_or_else(|| source.workspace_id.clone());
+    // Without a folder the copy stays in the original's folder, unless it goes to another workspace
+    let folder_id = match folder_id {
+        Some(fid) => Some(fid),
+        None if workspace_id == source.workspace_id => source.folder_id.clone(),
+        None => None,
+    };
+    check_move_target(&workspace_id, folder_id.as_deref())?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = now_iso();
+    let data = load_chat_data(&chat_id)?;
+    save_chat_data(&id, &data)?;
+
+    let meta = ChatMeta {
+        id: id.clone(),
+        chat_title: format!("{} (copy)", source.chat_title),
+        file_location: format!(".data/chats/{}.json", id),
+        workspace_id,
+        folder_id,
+        created_at: now.clone(),
+        last_updated_at: now,
+        pinned: false,
+        archived: false,
+        ..source
+    };
+
+    let mut index = load_chats_index()?;
+    index.chats.push(meta.clone());
+    save_chats_index(&index)?;
+    transfer_chat_between_folders(&id, None, meta.folder_id.as_deref())?;
+
+    Ok(meta)
+}
+
+/// Tauri command: Moves a chat to the trash, keeping its folder membership so it can be restored. Called from frontend when user deletes a chat.
+#[tauri::command]
+pub async fn delete_chat(chat_id: String) -> Result<(), String> {
+    let mut index = load_chats_index()?;
+
+    let position = index
+        .chats
+        .iter()
+        .position(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    let chat = index.chats.remove(position);
+
+    // The trash item is written before the chat leaves the index, so a failure never loses it
+    stash_chat_file(&chat_id)?;
+    let trash_id = match add_trash_item(
+        TrashItemKind::Chat,
+        chat.chat_title.clone(),
+        None,
+        vec![],
+        vec![chat.clone()],
+    ) {
+        Ok(id) => id,
+        Err(e) => {
+            undo_trash(&[], std::slice::from_ref(&chat_id));
+            return Err(e);
+        }
+    };
+    if let Err(e) = save_chats_index(&index) {
+        undo_trash(&[trash_id], std::slice::from_ref(&chat_id));
+        return Err(e);
     }
 
+    // A folder still listing the chat is repaired by the integrity check
+    if let Some(ref fid) = chat.folder_id {
+        if let Err(e) = remove_chat_from_folder_list(fid, &chat_id) {
+            eprintln!("Failed to remove chat {} from its folder: {}", chat_id, e);
+        }
+    }
+    remove_from_search_indexes(&[chat_id]);
     Ok(())
 }
 
-/// Tauri command: Searches chats by title within a workspace. Called from frontend chat search functionality.
+/// Tauri command: Searches chats by title and summary within a workspace, keeping only chats that carry every tag in `tags`. Archived chats are left out unless `include_archived` is true.
+/// Results are sorted like get_chats_for_workspace. Called from frontend chat search functionality.
 #[tauri::command]
-pub async fn search_chats(workspace_id: String, query: String) -> Result<Vec<ChatMeta>, String> {
+pub async fn search_chats(
+    workspace_id: String,
+    query: String,
+    tags: Option<Vec<String>>,
+    include_archived: Option<bool>,
+) -> Result<Vec<ChatMeta>, String> {
+    let include_archived = include_archived.unwrap_or(false);
     let index = load_chats_index()?;
     let query_lower = query.trim().to_lowercase();
 
-    if query_lower.is_empty() {
-        // Return all chats for the workspace
-        let filtered: Vec<ChatMeta> = index
-            .chats
-            .into_iter()
-            .filter(|c| c.workspace_id == workspace_id)
-            .collect();
-        return Ok(filtered);
-    }
-
-    let filtered: Vec<ChatMeta> = index
+    // An empty query matches every chat in the workspace
+    let mut filtered: Vec<ChatMeta> = index
         .chats
         .into_iter()
         .filter(|c| {
-            c.workspace_id == workspace_id && c.chat_title.to_lowercase().contains(&query_lower)
+            c.workspace_id == workspace_id
+                && (include_archived || !c.archived)
+                && has_all_tags(&c.tags, tags.as_deref())
+                && (query_lower.is_empty()
+                    || c.chat_title.to_lowercase().contains(&query_lower)
+                    || c.summary
+                        .as_ref()
+                        .is_some_and(|s| s.content.to_lowercase().contains(&query_lower)))
         })
         .collect();
+    sort_chats(&mut filtered);
 
     Ok(filtered)
 }
 
+/// Applies a change to one chat's flags and saves the index. Used by the pin, star and archive commands.
+fn update_chat_flags(
+    chat_id: &str,
+    update: impl FnOnce(&mut ChatMeta),
+) -> Result<ChatMeta, String> {
+    let mut index = load_chats_index()?;
+    let chat = index
+        .chats
+        .iter_mut()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+    update(chat);
+    let meta = chat.clone();
+    save_chats_index(&index)?;
+    Ok(meta)
+}
+
+/// Tauri command: Pins or unpins a chat. Pinned chats are listed first. Called from frontend chat menu.
+#[tauri::command]
+pub async fn set_chat_pinned(chat_id: String, pinned: bool) -> Result<ChatMeta, String> {
+    update_chat_flags(&chat_id, |c| c.pinned = pinned)
+}
+
+/// Tauri command: Stars or unstars a chat. Called from frontend chat menu.
+#[tauri::command]
+pub async fn set_chat_starred(chat_id: String, starred: bool) -> Result<ChatMeta, String> {
+    update_chat_flags(&chat_id, |c| c.starred = starred)
+}
+
+/// Tauri command: Archives or unarchives a chat. A pinned chat stays pinned, so it is back at the top when unarchived. Called from frontend chat menu.
+#[tauri::command]
+pub async fn set_chat_archived(chat_id: String, archived: bool) -> Result<ChatMeta, String> {
+    update_chat_flags(&chat_id, |c| c.archived = archived)
+}
+
 /// Helper: removes a chat_id from a folder's chat_ids list. Called internally when deleting a chat that belongs to a folder.
 fn remove_chat_from_folder_list(folder_id: &str, chat_id: &str) -> Result<(), String> {
     let mut folders_index = crate::api::folders::folders_storage::load_folders_index()?;
@@ -0,0 +1,166 @@
+use super::chat_completion::{complete_chat, completion_message};
+use super::chat_storage::{
+    get_chat_meta, load_chat_data, now_iso, set_chat_summary, ChatMessage, ChatSummary,
+};
+use crate::api::settings::settings_storage::{load_settings, DEFAULT_CONTEXT_WINDOW};
+
+/// Characters per token assumed when sizing map-reduce chunks. Deliberately lower than the usual estimate so chunks leave room for the prompt.
+const CHUNK_CHARS_PER_TOKEN: usize = 3;
+/// Smallest chunk size used for map-reduce, whatever the context window.
+const MIN_CHUNK_CHARS: usize = 2000;
+
+/// Instructions for the final pass, which produces the stored digest.
+const DIGEST_PROMPT: &str = "Write a meeting-note style digest of the conversation below between a user and an AI assistant. \
+     Use these Markdown sections: Summary, Key points, Decisions, Open questions and next steps. \
+     Keep it factual and concise, and keep names, commands, error messages and numbers exact. \
+     Reply with the digest only.";
+
+/// Instructions for the map pass over one part of a long conversation.
+const NOTES_PROMPT: &str = "You are taking notes on one part of a longer conversation between a user and an AI assistant. \
+     List the problems discussed, what was tried, findings, decisions and anything left open. \
+     Keep names, commands, error messages and numbers exact. Reply with the notes only.";
+
+/// Instructions for merging several sets of notes when they are still too long for one pass.
+const MERGE_PROMPT: &str = "Merge the following notes, taken on consecutive parts of one conversation, into a single set of notes. \
+     Remove repetition but keep every distinct fact, decision and open question. Reply with the notes only.";
+
+/// Formats a message for the summarization transcript.
+fn transcript_line(message: &ChatMessage) -> String {
+    format!("{}: {}", message.role, message.content.trim())
+}
+
+/// Packs text parts into chunks of at most `max_chars` characters, splitting parts that are longer than a chunk on character boundaries.
+fn pack_chunks(parts: Vec<String>, max_chars: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = vec![];
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for part in parts {
+        let pieces: Vec<String> = if part.chars().count() > max_chars {
+            part.chars()
+                .collect::<Vec<char>>()
+                .chunks(max_chars)
+                .map(|c| c.iter().collect())
+                .collect()
+        } else {
+            vec![part]
+        };
+
+        for piece in pieces {
+            let len = piece.chars().count();
+            if current_len > 0 && current_len + len + 2 > max_chars {
+                chunks.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            if current_len > 0 {
+                current.push_str("\n\n");
+                current_len += 2;
+            }
+            current.push_str(&piece);
+            current_len += len;
+        }
+    }
+    if current_len > 0 {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Runs one summarization prompt over a piece of text.
+async fn run_prompt(
+    model: &str,
+    instructions: &str,
+    text: String,
+    num_ctx: u32,
+) -> Result<String, String> {
+    let reply = complete_chat(
+        model,
+        vec![
+            completion_message("system", instructions),
+            completion_message("user", text),
+        ],
+        Some(serde_json::json!({ "num_ctx": num_ctx, "temperature": 0.2 })),
+    )
+    .await?;
+    Ok(reply.trim().to_string())
+}
+
+/// Summarizes messages with map-reduce: chunks that fit the context window are turned into notes, notes are merged until they fit one pass, and the final pass writes the digest.
+async fn summarize_messages(
+    model: &str,
+    messages: &[ChatMessage],
+    max_chars: usize,
+    num_ctx: u32,
+) -> Result<String, String> {
+    let chunks = pack_chunks(messages.iter().map(transcript_line).collect(), max_chars);
+    if chunks.len() <= 1 {
+        let transcript = chunks.into_iter().next().unwrap_or_default();
+        return run_prompt(model, DIGEST_PROMPT, transcript, num_ctx).await;
+    }
+
+    // Map: notes for each part of the conversation
+    let total = chunks.len();
+    let mut notes = Vec::with_capacity(total);
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let text = format!("Part {} of {}:\n\n{}", i + 1, total, chunk);
+        notes.push(run_prompt(model, NOTES_PROMPT, text, num_ctx).await?);
+    }
+
+    // Reduce: merge notes until they fit into a single pass
+    loop {
+        let groups = pack_chunks(notes.clone(), max_chars);
+        if groups.len() <= 1 || groups.len() >= notes.len() {
+            // Notes that cannot be merged any further are cut to fit the final pass
+            let combined: String = groups.join("\n\n").chars().take(max_chars).collect();
+            return run_prompt(model, DIGEST_PROMPT, combined, num_ctx).await;
+        }
+
+        let mut merged = Vec::with_capacity(groups.len());
+        for group in groups {
+            merged.push(run_prompt(model, MERGE_PROMPT, group, num_ctx).await?);
+        }
+        notes = merged;
+    }
+}
+
+/// Tauri command: Summarizes a chat into a meeting-note style digest and stores it on the chat's metadata.
+/// Uses the given model, or the chat's own model if none is given. Long chats are summarized with chunked map-reduce.
+#[tauri::command]
+pub async fn summarize_chat(chat_id: String, model: Option<String>) -> Result<ChatSummary, String> {
+    let meta = get_chat_meta(&chat_id)?;
+    let data = load_chat_data(&chat_id)?;
+
+    let messages: Vec<ChatMessage> = data
+        .messages
+        .into_iter()
+        .filter(|m| !m.content.trim().is_empty())
+        .collect();
+    if messages.is_empty() {
+        return Err("Cannot summarize a chat without messages".to_string());
+    }
+
+    let model = model
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or(meta.model_used);
+
+    let settings = load_settings()?;
+    let num_ctx = settings.context_window.unwrap_or(DEFAULT_CONTEXT_WINDOW);
+    let max_chars = (num_ctx.saturating_sub(settings.context_response_reserve) as usize
+        * CHUNK_CHARS_PER_TOKEN)
+        .max(MIN_CHUNK_CHARS);
+
+    let content = summarize_messages(&model, &messages, max_chars, num_ctx).await?;
+    if content.is_empty() {
+        return Err(format!("Model '{}' returned an empty summary", model));
+    }
+
+    let summary = ChatSummary {
+        content,
+        model,
+        message_count: messages.len(),
+        created_at: now_iso(),
+    };
+    set_chat_summary(&chat_id, summary.clone())?;
+    Ok(summary)
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use super::chat_completion::{complete_chat, completion_message};
+use super::chat_storage::{get_chat_meta, load_chat_data, set_generated_title};
+use crate::api::settings::settings_storage::load_settings;
+
+/// Event emitted to frontend when a chat's title was generated by the model.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatTitleUpdatedEvent {
+    pub chat_id: String,
+    pub title: String,
+}
+
+/// Maximum number of characters kept from a generated title.
+const MAX_TITLE_CHARS: usize = 80;
+/// Maximum number of characters of each message included in the titling prompt.
+const MAX_PROMPT_CHARS: usize = 2000;
+
+/// Returns at most `max` characters of `text`, cutting on a character boundary.
+fn truncate_chars(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
+}
+
+/// Cleans up a model-generated title: first line only, without quotes, trailing punctuation or a "Title:" prefix.
+fn clean_title(raw: &str) -> String {
+    let line = raw.trim().lines().next().unwrap_or_default().trim();
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line)
+        .trim();
+    let line = line
+        .trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c == '`')
+        .trim_end_matches(['.', '!'])
+        .trim();
+    truncate_chars(line, MAX_TITLE_CHARS)
+}
+
+/// Asks the configured title model for a concise title based on the first exchange of a chat.
+async fn generate_title(chat_id: &str) -> Result<Option<String>, String> {
+    let settings = load_settings()?;
+    if !settings.auto_title_enabled {
+        return Ok(None);
+    }
+
+    let meta = get_chat_meta(chat_id)?;
+    if meta.custom_title {
+        return Ok(None);
+    }
+
+    let data = load_chat_data(chat_id)?;
+    let first_user = data.messages.iter().find(|m| m.role == "user");
+    let first_reply = data.messages.iter().find(|m| m.role == "assistant");
+    let (Some(user), Some(reply)) = (first_user, first_reply) else {
+        return Ok(None);
+    };
+
+    let model = settings
+        .title_model
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or(meta.model_used);
+
+    let prompt = format!(
+        "Write a concise title of at most six words for the conversation below. \
+         Reply with the title only, without quotes or punctuation at the end.\n\n\
+         User: {}\n\nAssistant: {}",
+        truncate_chars(&user.content, MAX_PROMPT_CHARS),
+        truncate_chars(&reply.content, MAX_PROMPT_CHARS)
+    );
+
+    let reply = complete_chat(
+        &model,
+        vec![completion_message("user", prompt)],
+        Some(serde_json::json!({ "temperature": 0.2 })),
+    )
+    .await?;
+
+    let title = clean_title(&reply);
+    if title.is_empty() {
+        return Ok(None);
+    }
+
+    // The user may have renamed the chat while the model was working
+    if !set_generated_title(chat_id, &title)? {
+        return Ok(None);
+    }
+    Ok(Some(title))
+}
+
+/// Queues background generation of a chat title after the first assistant reply and emits `chat-title-updated` when the title changes.
+pub fn schedule_auto_title(app: tauri::AppHandle, chat_id: String) {
+    tauri::async_runtime::spawn(async move {
+        match generate_title(&chat_id).await {
+            Ok(Some(title)) => {
+                let _ = app.emit(
+                    "chat-title-updated",
+                    ChatTitleUpdatedEvent { chat_id, title },
+                );
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to generate title for chat {}: {}", chat_id, e),
+        }
+    });
+}
@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::chat_completion::{
+    complete_chat, completion_message, count_prompt_tokens, CompletionMessage,
+};
+use super::chat_storage::{now_iso, ChatData, ContextSummary};
+use crate::api::settings::settings_storage::{
+    AppSettings, ContextStrategy, DEFAULT_CONTEXT_WINDOW,
+};
+
+/// Rough number of characters per token used when estimating prompt size.
+const CHARS_PER_TOKEN: usize = 4;
+/// Tokens added per message for role markers and the model's chat template.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// Maximum number of characters of each message included in a summarization request.
+const MAX_SUMMARY_INPUT_CHARS: usize = 2000;
+/// How long a message waits for the history to be summarized before it is sent without a fresh summary.
+const SUMMARY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Describes how a chat's history was fitted into the context window for one request. Sent to the frontend with the final stream event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContextReport {
+    pub strategy: ContextStrategy,
+    pub context_window: u32,
+    pub estimated_tokens: usize,
+    pub exact_count: bool,
+    pub total_messages: usize,
+    pub sent_messages: usize,
+    pub dropped_messages: Vec<usize>,
+    pub summarized_messages: usize,
+    pub summary_error: Option<String>,
+    pub overflow: bool,
+    pub prompt_tokens: Option<u64>,
+}
+
+/// The messages chosen for a request: an optional pinned summary (sent first as a system message), the indexes of history messages to send, and the report.
+pub struct ContextSelection {
+    pub summary: Option<String>,
+    pub kept: Vec<usize>,
+    pub report: ContextReport,
+}
+
+/// Estimates token counts from character counts, optionally scaled to match Ollama's real count.
+struct TokenCounter {
+    scale: f64,
+}
+
+impl TokenCounter {
+    /// Unscaled estimate for a single message.
+    fn estimate(text: &str) -> usize {
+        text.chars().count().div_ceil(CHARS_PER_TOKEN) + MESSAGE_OVERHEAD_TOKENS
+    }
+
+    /// Estimate for a single message after calibration.
+    fn count(&self, text: &str) -> usize {
+        (Self::estimate(text) as f64 * self.scale).ceil() as usize
+    }
+}
+
+/// Formats a stored summary as the system message that replaces the summarized turns.
+fn summary_message(content: &str) -> String {
+    format!(
+        "Summary of the earlier part of this conversation:\n{}",
+        content
+    )
+}
+
+/// Runs a dry request through Ollama and returns the ratio between its token count and our estimate. Returns None if Ollama could not be asked.
+async fn calibrate(
+    model: &str,
+    data: &ChatData,
+    extra_context: &[String],
+    window: u32,
+) -> Option<f64> {
+    let mut messages: Vec<CompletionMessage> = data
+        .messages
+        .iter()
+        .map(|m| completion_message(&m.role, m.content.clone()))
+        .collect();
+    messages.extend(
+        extra_context
+            .iter()
+            .map(|c| completion_message("system", c.clone())),
+    );
+    let estimated: usize = messages
+        .iter()
+        .map(|m| TokenCounter::estimate(&m.content))
+        .sum();
+
+    match count_prompt_tokens(model, messages, window).await {
+        Ok(exact) if estimated > 0 => {
+            let scale = exact as f64 / estimated as f64;
+            // A count at the window size means Ollama truncated the prompt, so it is only a lower bound
+            Some(if exact >= window as u64 {
+                scale.max(1.0)
+            } else {
+                scale
+            })
+        }
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!(
+                "Failed to count prompt tokens, falling back to estimates: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Asks the model to fold the given messages into the previous summary. Used by the summarize strategy.
+async fn summarize_messages(
+    model: &str,
+    data: &ChatData,
+    previous: Option<&str>,
+    indexes: &[usize],
+    window: u32,
+) -> Result<String, String> {
+    let mut transcript = String::new();
+    if let Some(previous) = previous {
+        transcript.push_str(&format!("Previous summary:\n{}\n\n", previous));
+    }
+    transcript.push_str("New messages:\n");
+    for &i in indexes {
+        let message = &data.messages[i];
+        let content: String = message
+            .content
+            .chars()
+            .take(MAX_SUMMARY_INPUT_CHARS)
+            .collect();
+        transcript.push_str(&format!("{}: {}\n\n", message.role, content));
+    }
+
+    let messages = vec![
+        completion_message(
+            "system",
+            "You maintain a running summary of a conversation so it can continue after older \
+             messages are removed from the model's context. Merge the new messages into the \
+             previous summary. Keep facts, decisions, names, numbers and open questions. \
+             Reply with the summary only.",
+        ),
+        completion_message("user", transcript),
+    ];
+
+    let summary = complete_chat(
+        model,
+        messages,
+        Some(serde_json::json!({ "num_ctx": window, "temperature": 0.2 })),
+    )
+    .await?;
+
+    let summary = summary.trim().to_string();
+    if summary.is_empty() {
+        return Err("The model returned an empty summary".to_string());
+    }
+    Ok(summary)
+}
+
+/// Chooses which messages of a chat fit into a context window of `num_ctx` tokens (DEFAULT_CONTEXT_WINDOW if the request sets none), applying the configured strategy.
+/// System messages and the newest message are always kept. `extra_context` holds messages sent alongside the history (e.g. RAG excerpts).
+/// The summarize strategy may update `data.context_summary`; the caller is responsible for saving the chat.
+/// Failures to count or summarize fall back to estimates, the previous summary and plain truncation so a message can always be sent; summarizing is cut off after SUMMARY_TIMEOUT.
+pub async fn select_context(
+    model: &str,
+    data: &mut ChatData,
+    extra_context: &[String],
+    num_ctx: Option<u32>,
+    settings: &AppSettings,
+) -> ContextSelection {
+    let window = num_ctx.unwrap_or(DEFAULT_CONTEXT_WINDOW);
+    let budget = window.saturating_sub(settings.context_response_reserve) as usize;
+    let strategy = settings.context_strategy;
+    let total = data.messages.len();
+
+    let mut counter = TokenCounter { scale: 1.0 };
+    let mut exact_count = false;
+    if settings.context_exact_token_count {
+        if let Some(scale) = calibrate(model, data, extra_context, window).await {
+            counter.scale = scale;
+            exact_count = true;
+        }
+    }
+
+    let costs: Vec<usize> = data
+        .messages
+        .iter()
+        .map(|m| counter.count(&m.content))
+        .collect();
+    let extra_tokens: usize = extra_context.iter().map(|c| counter.count(c)).sum();
+
+    // Oldest first; system messages and the newest message are never dropped
+    let droppable: Vec<usize> = (0..total.saturating_sub(1))
+        .filter(|&i| data.messages[i].role != "system")
+        .collect();
+
+    let mut keep = vec![true; total];
+    let mut used = costs.iter().sum::<usize>() + extra_tokens;
+    let mut summary: Option<String> = None;
+    let mut summarized_messages = 0;
+    let mut summary_error = None;
+
+    if strategy == ContextStrategy::KeepLast {
+        // The newest message counts towards the kept messages
+        let cut = droppable
+            .len()
+            .saturating_sub(settings.context_keep_last.saturating_sub(1));
+        for &i in &droppable[..cut] {
+            keep[i] = false;
+            used -= costs[i];
+        }
+    }
+
+    if strategy == ContextStrategy::Summarize && used > budget {
+        // A summary is only reusable if the history it covers is still intact
+        let existing = data
+            .context_summary
+            .clone()
+            .filter(|s| s.covers_messages < total);
+        let covered = existing.as_ref().map_or(0, |s| s.covers_messages);
+
+        let mut without_summary = used;
+        for &i in droppable.iter().filter(|&&i| i < covered) {
+            without_summary -= costs[i];
+        }
+        let existing_fits = existing.as_ref().is_some_and(|s| {
+            without_summary + counter.count(&summary_message(&s.content)) <= budget
+        });
+
+        let content = if existing_fits {
+            existing.map(|s| s.content)
+        } else {
+            // Drop more turns, leaving a quarter of the budget for the new summary
+            let target = budget - budget / 4;
+            let mut newly_covered = vec![];
+            for &i in droppable.iter().filter(|&&i| i >= covered) {
+                if without_summary <= target {
+                    break;
+                }
+                without_summary -= costs[i];
+                newly_covered.push(i);
+            }
+
+            if newly_covered.is_empty() {
+                existing.map(|s| s.content)
+            } else {
+                let previous = existing.as_ref().map(|s| s.content.as_str());
+                let summarized = tokio::time::timeout(
+                    SUMMARY_TIMEOUT,
+                    summarize_messages(model, data, previous, &newly_covered, window),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(format!(
+                        "Summarizing the chat history took longer than {} seconds",
+                        SUMMARY_TIMEOUT.as_secs()
+                    ))
+                });
+                match summarized {
+                    Ok(content) => {
+                        data.context_summary = Some(ContextSummary {
+                            content: content.clone(),
+                            covers_messages: newly_covered[newly_covered.len() - 1] + 1,
+                            created_at: now_iso(),
+                        });
+                        Some(content)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to summarize chat history: {}", e);
+                        summary_error = Some(e);
+                        // The previous summary still stands in for the turns it covers
+                        existing.map(|s| s.content)
+                    }
+                }
+            }
+        };
+
+        if let Some(content) = content {
+            let covers = data
+                .context_summary
+                .as_ref()
+                .map_or(covered, |s| s.covers_messages);
+            for &i in droppable.iter().filter(|&&i| i < covers) {
+                keep[i] = false;
+                used -= costs[i];
+                summarized_messages += 1;
+            }
+            let text = summary_message(&content);
+            used += counter.count(&text);
+            summary = Some(text);
+        }
+    }
+
+    // Whatever the strategy, drop the oldest remaining turns until the prompt fits
+    for &i in &droppable {
+        if used <= budget {
+            break;
+        }
+        if keep[i] {
+            keep[i] = false;
+            used -= costs[i];
+        }
+    }
+
+    let kept: Vec<usize> = (0..total).filter(|&i| keep[i]).collect();
+    let dropped_messages: Vec<usize> = (0..total).filter(|&i| !keep[i]).collect();
+
+    let report = ContextReport {
+        strategy,
+        context_window: window,
+        estimated_tokens: used,
+        exact_count,
+        total_messages: total,
+        sent_messages: kept.len() + usize::from(summary.is_some()),
+        dropped_messages,
+        summarized_messages,
+        summary_error,
+        overflow: used > budget,
+        prompt_tokens: None,
+    };
+
+    ContextSelection {
+        summary,
+        kept,
+        report,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::chats::chat_storage::ChatMessage;
+
+    /// Each message costs 13 estimated tokens: 36 characters plus the per-message overhead.
+    const MESSAGE_TOKENS: usize = 13;
+
+    fn chat(roles: &[&str]) -> ChatData {
+        ChatData {
+            messages: roles
+                .iter()
+                .map(|role| ChatMessage {
+                    role: role.to_string(),
+                    content: "x".repeat(36),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn conversation(turns: usize) -> Vec<&'static str> {
+        (0..turns)
+            .map(|i| if i % 2 == 0 { "user" } else { "assistant" })
+            .collect()
+    }
+
+    /// 80 tokens of a 100 token window are left for the history, which fits six messages.
+    fn settings(strategy: ContextStrategy, keep_last: usize) -> AppSettings {
+        AppSettings {
+            context_response_reserve: 20,
+            context_strategy: strategy,
+            context_keep_last: keep_last,
+            context_exact_token_count: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn sliding_window_drops_the_oldest_messages() {
+        let mut data = chat(&conversation(10));
+        let selection = select_context(
+            "model",
+            &mut data,
+            &[],
+            Some(100),
+            &settings(ContextStrategy::SlidingWindow, 20),
+        )
+        .await;
+
+        assert_eq!(selection.kept, [4, 5, 6, 7, 8, 9]);
+        assert_eq!(selection.report.dropped_messages, [0, 1, 2, 3]);
+        assert_eq!(selection.report.estimated_tokens, 6 * MESSAGE_TOKENS);
+        assert_eq!(selection.report.context_window, 100);
+        assert!(!selection.report.overflow);
+        assert!(selection.summary.is_none());
+    }
+
+    #[tokio::test]
+    async fn sliding_window_keeps_system_messages_and_counts_extra_context() {
+        let mut roles = vec!["system"];
+        roles.extend(conversation(9));
+        let mut data = chat(&roles);
+        let selection = select_context(
+            "model",
+            &mut data,
+            &["y".repeat(36)],
+            Some(100),
+            &settings(ContextStrategy::SlidingWindow, 20),
+        )
+        .await;
+
+        assert_eq!(selection.kept, [0, 6, 7, 8, 9]);
+        assert_eq!(selection.report.estimated_tokens, 6 * MESSAGE_TOKENS);
+    }
+
+    #[tokio::test]
+    async fn sliding_window_leaves_a_fitting_history_alone() {
+        let mut data = chat(&conversation(4));
+        let selection = select_context(
+            "model",
+            &mut data,
+            &[],
+            None,
+            &settings(ContextStrategy::SlidingWindow, 20),
+        )
+        .await;
+
+        assert_eq!(selection.kept, [0, 1, 2, 3]);
+        assert!(selection.report.dropped_messages.is_empty());
+        assert_eq!(selection.report.context_window, DEFAULT_CONTEXT_WINDOW);
+    }
+
+    #[tokio::test]
+    async fn keep_last_keeps_system_messages_and_the_last_messages() {
+        let mut roles = vec!["system"];
+        roles.extend(conversation(6));
+        let mut data = chat(&roles);
+        let selection = select_context(
+            "model",
+            &mut data,
+            &[],
+            None,
+            &settings(ContextStrategy::KeepLast, 3),
+        )
+        .await;
+
+        assert_eq!(selection.kept, [0, 4, 5, 6]);
+        assert_eq!(selection.report.dropped_messages, [1, 2, 3]);
+        assert_eq!(selection.report.sent_messages, 4);
+    }
+
+    #[tokio::test]
+    async fn keep_last_still_trims_to_the_window() {
+        let mut data = chat(&conversation(10));
+        let selection = select_context(
+            "model",
+            &mut data,
+            &[],
+            Some(100),
+            &settings(ContextStrategy::KeepLast, 8),
+        )
+        .await;
+
+        assert_eq!(selection.kept, [4, 5, 6, 7, 8, 9]);
+        assert!(!selection.report.overflow);
+    }
+
+    #[tokio::test]
+    async fn the_newest_message_is_always_kept() {
+        let mut data = chat(&conversation(3));
+        data.messages[2].content = "z".repeat(1000);
+        for strategy in [ContextStrategy::SlidingWindow, ContextStrategy::KeepLast] {
+            let selection =
+                select_context("model", &mut data, &[], Some(100), &settings(strategy, 1)).await;
+
+            assert_eq!(selection.kept, [2]);
+            assert_eq!(selection.report.dropped_messages, [0, 1]);
+            assert!(selection.report.overflow);
+        }
+    }
+}
@@ -1,18 +1,38 @@
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tauri::Emitter;
 
+use super::chat_completion::{completion_message, CompletionMessage};
 use super::chat_storage::{
-    create_new_chat, load_chat_data, save_chat_data, update_chat_timestamp, ChatData, ChatMessage,
+    create_new_chat, get_chat_meta, load_chat_data, now_iso, record_reply_model, save_chat_data,
+    set_chat_rag, ChatData, ChatMessage, MessageCitation,
 };
+use super::chat_titles::schedule_auto_title;
+use super::context_manager::{select_context, ContextReport};
+use super::structured_output::{check_format, resolve_structured_reply, MAX_FORMAT_RETRIES};
+use super::think_parser::{model_supports_thinking, ThinkSplitter};
+use crate::api::presets::preset_storage::get_preset_by_id;
+use crate::api::prompts::prompt_render::render_template;
+use crate::api::prompts::prompt_storage::{get_prompt_template, PromptTemplate};
+use crate::api::rag::document_index::{retrieve_chunks, RetrievedChunk};
+use crate::api::settings::settings_storage::load_settings;
 use crate::api::workspace::workspace_storage::load_workspaces_index;
 
-/// Request body sent to Ollama's /api/chat endpoint. Contains model name, messages history, and streaming flag.
+/// Request body sent to Ollama's /api/chat endpoint. Contains model name, messages history, streaming flag, model options such as num_ctx and how long to keep the model loaded.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct OllamaChatRequest {
     model: String,
     messages: Vec<OllamaChatMessage>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<serde_json::Value>,
 }
 
 /// Represents a single message in the Ollama chat format (role and content).
@@ -38,20 +58,50 @@ struct OllamaStreamChunk {
     eval_duration: Option<u64>,
 }
 
-/// Represents the message field within an Ollama stream chunk.
+/// Represents the message field within an Ollama stream chunk. `thinking` is set by reasoning models when `think` is enabled.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct OllamaChunkMessage {
     role: Option<String>,
     content: Option<String>,
+    thinking: Option<String>,
 }
 
-/// Event emitted to frontend during streaming response. Contains chat_id, content chunk, done flag, and optional done_reason.
+/// Event emitted to frontend during streaming response. Contains chat_id, content chunk, reasoning chunk, done flag, and optional done_reason.
+/// The final event also carries the context report describing which messages were dropped or summarized.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatStreamEvent {
     pub chat_id: String,
     pub content: String,
+    pub thinking: String,
     pub done: bool,
     pub done_reason: Option<String>,
+    pub context: Option<ContextReport>,
+}
+
+/// Optional per-request settings for send_chat_message. Missing fields keep the default behaviour.
+/// `format` ("json" or a JSON Schema) constrains the reply; it is validated once the stream finishes and retried up to `format_retries` times.
+/// `think` asks reasoning models to return their reasoning separately from the reply.
+/// `template_id` sends a rendered prompt template instead of the message text, filled in from `variables`.
+/// `preset_id` starts a new chat from a preset; the preset's model replaces `model`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SendMessageOptions {
+    pub format: Option<serde_json::Value>,
+    pub format_retries: Option<u32>,
+    pub think: Option<bool>,
+    pub template_id: Option<String>,
+    pub variables: BTreeMap<String, String>,
+    pub preset_id: Option<String>,
+}
+
+/// Event emitted to frontend after a structured (JSON) reply was validated. `content` is the final reply text, which differs from the streamed text if a retry was needed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatStructuredEvent {
+    pub chat_id: String,
+    pub content: String,
+    pub value: Option<serde_json::Value>,
+    pub errors: Vec<String>,
+    pub attempts: u32,
 }
 
 /// Error event emitted to frontend when Ollama request fails. Contains chat_id and error message.
@@ -61,62 +111,305 @@ pub struct ChatStreamError {
     pub error: String,
 }
 
+/// Builds the system message that injects retrieved document chunks as numbered context for the model.
+fn build_rag_context(chunks: &[RetrievedChunk]) -> String {
+    let mut context = String::from(
+        "Answer using the following excerpts from the workspace documents when they are relevant. \
+         Cite excerpts you rely on by their number in square brackets, e.g. [1].\n",
+    );
+    for (i, chunk) in chunks.iter().enumerate() {
+        context.push_str(&format!(
+            "\n[{}] {} (lines {}-{})\n{}\n",
+            i + 1,
+            chunk.source_path,
+            chunk.start_line,
+            chunk.end_line,
+            chunk.text
+        ));
+    }
+    context
+}
+
+/// Converts retrieved chunks into citations, marking the ones the reply referenced as [n].
+fn build_citations(chunks: &[RetrievedChunk], reply: &str) -> Vec<MessageCitation> {
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| MessageCitation {
+            chunk_id: chunk.chunk_id.clone(),
+            source_path: chunk.source_path.clone(),
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            score: chunk.score,
+            cited: reply.contains(&format!("[{}]", i + 1)),
+        })
+        .collect()
+}
+
+/// Separates a stream chunk's reply text from its reasoning, combining Ollama's `thinking` field with inline `<think>` blocks.
+/// Without a splitter the content is passed through as is. Flushes the splitter on the final chunk.
+fn split_chunk(
+    splitter: Option<&mut ThinkSplitter>,
+    chunk: &OllamaStreamChunk,
+) -> (String, String) {
+    let message = chunk.message.as_ref();
+    let raw = message.and_then(|m| m.content.clone()).unwrap_or_default();
+    let mut thinking = message.and_then(|m| m.thinking.clone()).unwrap_or_default();
+
+    let Some(splitter) = splitter else {
+        return (raw, thinking);
+    };
+    let (mut content, inline_thinking) = splitter.push(&raw);
+    thinking.push_str(&inline_thinking);
+
+    if chunk.done {
+        let (rest, rest_thinking) = splitter.finish();
+        content.push_str(&rest);
+        thinking.push_str(&rest_thinking);
+    }
+    (content, thinking)
+}
+
+/// Appends the completed assistant reply to the chat file and records its model on the chat's metadata. Called once the stream reports done.
+/// After the first reply of a chat, queues automatic titling.
+fn save_assistant_reply(app: &tauri::AppHandle, chat_id: &str, reply: ChatMessage) {
+    let mut final_data = load_chat_data(chat_id).unwrap_or_default();
+    let first_reply = !final_data.messages.iter().any(|m| m.role == "assistant");
+
+    let model = reply.model.clone();
+    final_data.messages.push(reply);
+
+    let _ = save_chat_data(chat_id, &final_data);
+    if let Some(ref model) = model {
+        let _ = record_reply_model(chat_id, model);
+    }
+
+    if first_reply {
+        schedule_auto_title(app.clone(), chat_id.to_string());
+    }
+}
+
+/// Rejects a workspace-scoped prompt template used in a chat from another workspace.
+fn check_template_scope(
+    template: Option<&PromptTemplate>,
+    workspace_id: &str,
+) -> Result<(), String> {
+    match template.and_then(|t| t.workspace_id.as_deref()) {
+        Some(scope) if scope != workspace_id => Err(format!(
+            "Prompt '{}' belongs to a different workspace",
+            template.map(|t| t.name.as_str()).unwrap_or_default()
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// Tauri command: Sends a chat message to Ollama and streams the response back to the frontend.
 /// Handles both new chats and continuing existing conversations. Creates new chat if chat_id is None.
+/// When RAG is enabled for the chat (or `use_rag` turns it on), the top-k workspace document chunks are injected as context.
+/// Chats started from a preset send its system prompt, options and keep_alive with every message.
+/// See SendMessageOptions for structured output, prompt templates and presets; `message` may be omitted when a template is used.
 #[tauri::command]
 pub async fn send_chat_message(
     app: tauri::AppHandle,
     chat_id: Option<String>,
     model: String,
-    message: String,
+    message: Option<String>,
     workspace_id: Option<String>,
+    use_rag: Option<bool>,
+    options: Option<SendMessageOptions>,
 ) -> Result<String, String> {
-    // Determine if this is a new chat or an existing one
-    let (resolved_chat_id, mut chat_data) = if let Some(ref id) = chat_id {
-        let data = load_chat_data(id)?;
-        (id.clone(), data)
-    } else {
+    let options = options.unwrap_or_default();
+    if let Some(ref format) = options.format {
+        check_format(format)?;
+    }
+    let format_retries = options.format_retries.unwrap_or(0).min(MAX_FORMAT_RETRIES);
+
+    // Render the prompt template, if any, so missing variables fail before anything is saved
+    let template = match options.template_id {
+        Some(ref id) => Some(get_prompt_template(id)?),
+        None => None,
+    };
+    let message = match (template.as_ref(), message) {
+        (Some(_), Some(text)) if !text.trim().is_empty() => {
+            return Err("Send either a message or a prompt template, not both".to_string());
+        }
+        (Some(template), _) => render_template(template, &options.variables)?,
+        (None, Some(text)) => text,
+        (None, None) => return Err("Message cannot be empty".to_string()),
+    };
+
+    // A preset is applied when starting a chat; its model replaces the requested one
+    let start_preset = match (chat_id.as_ref(), options.preset_id.as_ref()) {
+        (None, Some(id)) => Some(get_preset_by_id(id)?),
+        (Some(_), Some(_)) => {
+            return Err("A preset can only be applied when starting a chat".to_string());
+        }
+        _ => None,
+    };
+    let model = start_preset
+        .as_ref()
+        .map_or(model, |p| p.settings.model.clone());
+    let use_rag = use_rag.or_else(|| {
+        start_preset
+            .as_ref()
+            .filter(|p| p.settings.tools.iter().any(|t| t == "rag"))
+            .map(|_| true)
+    });
+
+    // Work out the chat's workspace, RAG flag and preset before anything is written
+    let existing_meta = match chat_id {
+        Some(ref id) => Some(get_chat_meta(id)?),
+        None => None,
+    };
+    let chat_workspace = match existing_meta {
+        Some(ref meta) => meta.workspace_id.clone(),
         // Resolve workspace_id: use provided, or fall back to active workspace
-        let ws_id = match workspace_id {
+        None => match workspace_id {
             Some(ref id) if !id.is_empty() => id.clone(),
             _ => {
                 let ws_index = load_workspaces_index()?;
                 ws_index.active_workspace_id
             }
-        };
-        let meta = create_new_chat(&model, &message, &ws_id, None)?;
-        (meta.id, ChatData { messages: vec![] })
+        },
+    };
+    check_template_scope(template.as_ref(), &chat_workspace)?;
+    let rag_enabled = use_rag.unwrap_or(existing_meta.as_ref().is_some_and(|m| m.rag_enabled));
+    // A deleted preset no longer applies; the chat carries on with plain requests
+    let preset = match existing_meta {
+        Some(ref meta) => meta
+            .preset_id
+            .as_deref()
+            .and_then(|id| get_preset_by_id(id).ok()),
+        None => start_preset,
     };
+    let system_prompt = preset
+        .as_ref()
+        .and_then(|p| p.settings.system_prompt.clone());
+
+    // Retrieve document context before persisting anything so a failed lookup leaves no chat or message behind
+    let settings = load_settings()?;
+    let retrieved = if rag_enabled {
+        retrieve_chunks(&chat_workspace, &message, settings.rag_top_k).await?
+    } else {
+        vec![]
+    };
+
+    // Determine if this is a new chat or an existing one
+    let (resolved_chat_id, mut chat_data) = if let Some(ref id) = chat_id {
+        let data = load_chat_data(id)?;
+        (id.clone(), data)
+    } else {
+        let meta = create_new_chat(
+            &model,
+            &message,
+            &chat_workspace,
+            None,
+            options.preset_id.clone(),
+        )?;
+        (meta.id, ChatData::default())
+    };
+
+    if let Some(enabled) = use_rag {
+        set_chat_rag(&resolved_chat_id, enabled)?;
+    }
 
     // Append the user message to the conversation
     chat_data.messages.push(ChatMessage {
         role: "user".to_string(),
         content: message.clone(),
+        created_at: Some(now_iso()),
+        ..Default::default()
     });
 
+    // Fit the history into the context window; a new pinned summary is saved along with the user message
+    let rag_context = (!retrieved.is_empty()).then(|| build_rag_context(&retrieved));
+    let extra_context: Vec<String> = system_prompt
+        .iter()
+        .chain(rag_context.iter())
+        .cloned()
+        .collect();
+    // num_ctx is only sent when the preset or the user chose a context window; the preset's wins
+    let preset_num_ctx = preset
+        .as_ref()
+        .and_then(|p| p.settings.options.as_ref())
+        .and_then(|o| o.get("num_ctx"))
+        .and_then(|v| v.as_u64())
+        .and_then(|n| u32::try_from(n).ok());
+    let num_ctx = preset_num_ctx.or(settings.context_window);
+    let selection =
+        select_context(&model, &mut chat_data, &extra_context, num_ctx, &settings).await;
+
     // Save immediately so the user message is persisted
     save_chat_data(&resolved_chat_id, &chat_data)?;
 
-    // Build the Ollama request with full conversation history
-    let ollama_messages: Vec<OllamaChatMessage> = chat_data
-        .messages
+    // Build the Ollama request from the pinned summary and the messages that fit
+    let mut ollama_messages: Vec<OllamaChatMessage> = selection
+        .summary
         .iter()
-        .map(|m| OllamaChatMessage {
-            role: m.role.clone(),
-            content: m.content.clone(),
+        .map(|summary| OllamaChatMessage {
+            role: "system".to_string(),
+            content: summary.clone(),
         })
         .collect();
+    ollama_messages.extend(selection.kept.iter().map(|&i| {
+        let m = &chat_data.messages[i];
+        OllamaChatMessage {
+            role: m.role.clone(),
+            content: m.content.clone(),
+        }
+    }));
+
+    // Place the retrieved context right before the new user message; it is not persisted
+    if let Some(content) = rag_context {
+        ollama_messages.insert(
+            ollama_messages.len() - 1,
+            OllamaChatMessage {
+                role: "system".to_string(),
+                content,
+            },
+        );
+    }
+
+    // The preset's system prompt leads the conversation
+    if let Some(content) = system_prompt {
+        ollama_messages.insert(
+            0,
+            OllamaChatMessage {
+                role: "system".to_string(),
+                content,
+            },
+        );
+    }
+
+    // num_ctx follows the context window the history was fitted to
+    let mut request_options = preset
+        .as_ref()
+        .and_then(|p| p.settings.options.clone())
+        .filter(|o| o.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    if let Some(num_ctx) = num_ctx {
+        request_options["num_ctx"] = serde_json::json!(num_ctx);
+    }
 
     let request_body = OllamaChatRequest {
         model: model.clone(),
         messages: ollama_messages,
         stream: true,
+        format: options.format,
+        think: options.think,
+        options: Some(request_options),
+        keep_alive: preset.and_then(|p| p.settings.keep_alive),
     };
+    let context_report = selection.report;
 
     let chat_id_for_task = resolved_chat_id.clone();
 
     // Spawn a background task to handle streaming
     tauri::async_runtime::spawn(async move {
+        // Only reasoning models get inline <think> blocks split off, so other models keep a literal "<think>"
+        let split_inline_think =
+            request_body.think == Some(true) || model_supports_thinking(&request_body.model).await;
+
         let client = reqwest::Client::new();
 
         let response = match client
@@ -162,6 +455,9 @@ pub async fn send_chat_message(
         let mut stream = response.bytes_stream();
         let mut full_response = String::new();
         let mut buffer = String::new();
+        let mut full_thinking = String::new();
+        let mut splitter = split_inline_think.then(ThinkSplitter::default);
+        let mut finished = false;
 
         while let Some(chunk_result) = stream.next().await {
             match chunk_result {
@@ -184,36 +480,27 @@ pub async fn send_chat_message(
 
                         match serde_json::from_str::<OllamaStreamChunk>(&line) {
                             Ok(chunk) => {
-                                let content = chunk
-                                    .message
-                                    .as_ref()
-                                    .and_then(|m| m.content.clone())
-                                    .unwrap_or_default();
-
+                                let (content, thinking) = split_chunk(splitter.as_mut(), &chunk);
                                 full_response.push_str(&content);
+                                full_thinking.push_str(&thinking);
 
                                 let _ = app.emit(
                                     "chat-stream-chunk",
                                     ChatStreamEvent {
                                         chat_id: chat_id_for_task.clone(),
                                         content,
+                                        thinking,
                                         done: chunk.done,
                                         done_reason: chunk.done_reason.clone(),
+                                        context: chunk.done.then(|| ContextReport {
+                                            prompt_tokens: chunk.prompt_eval_count,
+                                            ..context_report.clone()
+                                        }),
                                     },
                                 );
 
                                 if chunk.done {
-                                    // Save the assistant's complete response
-                                    let mut final_data = load_chat_data(&chat_id_for_task)
-                                        .unwrap_or(ChatData { messages: vec![] });
-
-                                    final_data.messages.push(ChatMessage {
-                                        role: "assistant".to_string(),
-                                        content: full_response.clone(),
-                                    });
-
-                                    let _ = save_chat_data(&chat_id_for_task, &final_data);
-                                    let _ = update_chat_timestamp(&chat_id_for_task);
+                                    finished = true;
                                 }
                             }
                             Err(e) => {
@@ -239,38 +526,90 @@ pub async fn send_chat_message(
         let remaining = buffer.trim().to_string();
         if !remaining.is_empty() {
             if let Ok(chunk) = serde_json::from_str::<OllamaStreamChunk>(&remaining) {
-                let content = chunk
-                    .message
-                    .as_ref()
-                    .and_then(|m| m.content.clone())
-                    .unwrap_or_default();
-
+                let (content, thinking) = split_chunk(splitter.as_mut(), &chunk);
                 full_response.push_str(&content);
+                full_thinking.push_str(&thinking);
 
                 let _ = app.emit(
                     "chat-stream-chunk",
                     ChatStreamEvent {
                         chat_id: chat_id_for_task.clone(),
                         content,
+                        thinking,
                         done: chunk.done,
                         done_reason: chunk.done_reason.clone(),
+                        context: chunk.done.then(|| ContextReport {
+                            prompt_tokens: chunk.prompt_eval_count,
+                            ..context_report.clone()
+                        }),
                     },
                 );
 
                 if chunk.done {
-                    let mut final_data =
-                        load_chat_data(&chat_id_for_task).unwrap_or(ChatData { messages: vec![] });
-
-                    final_data.messages.push(ChatMessage {
-                        role: "assistant".to_string(),
-                        content: full_response.clone(),
-                    });
-
-                    let _ = save_chat_data(&chat_id_for_task, &final_data);
-                    let _ = update_chat_timestamp(&chat_id_for_task);
+                    finished = true;
                 }
             }
         }
+
+        if !finished {
+            return;
+        }
+
+        // Text after a closing </think> tag usually starts with blank lines
+        let thinking = full_thinking.trim();
+        let thinking = (!thinking.is_empty()).then(|| thinking.to_string());
+        if thinking.is_some() {
+            full_response = full_response.trim_start().to_string();
+        }
+
+        // Validate structured replies, retrying with the validation errors if allowed
+        let mut content = full_response;
+        let mut structured = None;
+        if let Some(ref format) = request_body.format {
+            let messages: Vec<CompletionMessage> = request_body
+                .messages
+                .iter()
+                .map(|m| completion_message(&m.role, m.content.clone()))
+                .collect();
+            let outcome = resolve_structured_reply(
+                &request_body.model,
+                messages,
+                format,
+                content,
+                format_retries,
+                request_body.options.clone(),
+            )
+            .await;
+
+            let _ = app.emit(
+                "chat-structured-output",
+                ChatStructuredEvent {
+                    chat_id: chat_id_for_task.clone(),
+                    content: outcome.content.clone(),
+                    value: outcome.value.clone(),
+                    errors: outcome.errors,
+                    attempts: outcome.attempts,
+                },
+            );
+            content = outcome.content;
+            structured = outcome.value;
+        }
+
+        // Save the assistant's complete response
+        let citations = build_citations(&retrieved, &content);
+        save_assistant_reply(
+            &app,
+            &chat_id_for_task,
+            ChatMessage {
+                role: "assistant".to_string(),
+                content,
+                created_at: Some(now_iso()),
+                citations,
+                structured,
+                thinking,
+                model: Some(request_body.model.clone()),
+            },
+        );
     });
 
     Ok(resolved_chat_id)
@@ -1,2 +1,10 @@
+pub mod chat_bulk;
+pub mod chat_completion;
+pub mod chat_listing;
 pub mod chat_storage;
+pub mod chat_summary;
+pub mod chat_titles;
+pub mod context_manager;
 pub mod generate_chat_message;
+pub mod structured_output;
+pub mod think_parser;
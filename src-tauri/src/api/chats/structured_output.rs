@@ -0,0 +1,121 @@
+use serde_json::Value;
+
+use super::chat_completion::{complete_chat_with_format, completion_message, CompletionMessage};
+
+/// Upper bound on automatic retries for a structured reply, whatever the caller asks for.
+pub const MAX_FORMAT_RETRIES: u32 = 3;
+
+/// The final state of a structured reply after validation and any retries.
+pub struct StructuredOutcome {
+    pub content: String,
+    pub value: Option<Value>,
+    pub errors: Vec<String>,
+    pub attempts: u32,
+}
+
+/// Checks that a `format` value is either "json" or a valid JSON Schema. Used before a request is sent so bad schemas fail fast.
+pub fn check_format(format: &Value) -> Result<(), String> {
+    match format {
+        Value::String(s) if s == "json" => Ok(()),
+        Value::Object(_) => jsonschema::validator_for(format)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid JSON schema: {}", e)),
+        _ => Err("Format must be \"json\" or a JSON Schema object".to_string()),
+    }
+}
+
+/// Parses a reply as JSON and validates it against the format. Returns the parsed value, or readable errors prefixed with the failing JSON pointer.
+pub fn validate_reply(format: &Value, reply: &str) -> Result<Value, Vec<String>> {
+    let value: Value = serde_json::from_str(reply.trim())
+        .map_err(|e| vec![format!("Response is not valid JSON: {}", e)])?;
+
+    if format.is_object() {
+        let validator = jsonschema::validator_for(format)
+            .map_err(|e| vec![format!("Invalid JSON schema: {}", e)])?;
+        let errors: Vec<String> = validator
+            .iter_errors(&value)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{}: {}", path, e)
+                }
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+    }
+
+    Ok(value)
+}
+
+/// Builds the follow-up message sent to the model when its reply did not validate.
+fn retry_message(errors: &[String]) -> String {
+    let mut message =
+        String::from("Your previous response does not match the required JSON schema:\n");
+    for error in errors {
+        message.push_str(&format!("- {}\n", error));
+    }
+    message.push_str("Reply again with only a JSON value that fixes these errors.");
+    message
+}
+
+/// Validates a streamed reply and, while it is invalid and retries remain, asks the model again with the validation errors.
+/// `messages` is the conversation that produced the reply. Retries are non-streaming and use the same format.
+pub async fn resolve_structured_reply(
+    model: &str,
+    mut messages: Vec<CompletionMessage>,
+    format: &Value,
+    reply: String,
+    max_retries: u32,
+    options: Option<Value>,
+) -> StructuredOutcome {
+    let mut content = reply;
+    let mut attempts = 1;
+
+    loop {
+        let mut errors = match validate_reply(format, &content) {
+            Ok(value) => {
+                return StructuredOutcome {
+                    content,
+                    value: Some(value),
+                    errors: vec![],
+                    attempts,
+                }
+            }
+            Err(errors) => errors,
+        };
+
+        if attempts > max_retries {
+            return StructuredOutcome {
+                content,
+                value: None,
+                errors,
+                attempts,
+            };
+        }
+
+        messages.push(completion_message("assistant", content.clone()));
+        messages.push(completion_message("user", retry_message(&errors)));
+
+        match complete_chat_with_format(model, messages.clone(), format.clone(), options.clone())
+            .await
+        {
+            Ok(next) => {
+                content = next;
+                attempts += 1;
+            }
+            Err(e) => {
+                errors.push(format!("Retry failed: {}", e));
+                return StructuredOutcome {
+                    content,
+                    value: None,
+                    errors,
+                    attempts,
+                };
+            }
+        }
+    }
+}
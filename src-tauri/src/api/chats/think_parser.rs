@@ -0,0 +1,184 @@
+use crate::api::models::show_model_details::show_model_details;
+
+/// Tag that opens an inline reasoning block.
+const OPEN_TAG: &str = "<think>";
+/// Tag that closes an inline reasoning block.
+const CLOSE_TAG: &str = "</think>";
+
+/// Splits streamed text into reply content and reasoning for models that inline `<think>...</think>` blocks.
+/// Keeps state between chunks, so tags split across chunk boundaries are still recognised.
+#[derive(Debug, Default)]
+pub struct ThinkSplitter {
+    in_think: bool,
+    pending: String,
+}
+
+/// Returns the length of the longest suffix of `text` that is a proper prefix of `tag`.
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&k| text.ends_with(&tag[..k]))
+        .unwrap_or(0)
+}
+
+impl ThinkSplitter {
+    /// Feeds the next piece of streamed text and returns the (content, thinking) text that can be emitted so far.
+    pub fn push(&mut self, text: &str) -> (String, String) {
+        let mut buffer = std::mem::take(&mut self.pending);
+        buffer.push_str(text);
+
+        let mut content = String::new();
+        let mut thinking = String::new();
+
+        loop {
+            let tag = if self.in_think { CLOSE_TAG } else { OPEN_TAG };
+            let target = if self.in_think {
+                &mut thinking
+            } else {
+                &mut content
+            };
+
+            match buffer.find(tag) {
+                Some(pos) => {
+                    target.push_str(&buffer[..pos]);
+                    buffer = buffer[pos + tag.len()..].to_string();
+                    self.in_think = !self.in_think;
+                }
+                None => {
+                    // Hold back a possible partial tag until the next chunk arrives; tags are ASCII so the cut is a char boundary
+                    let keep = partial_tag_len(&buffer, tag);
+                    let cut = buffer.len() - keep;
+                    target.push_str(&buffer[..cut]);
+                    self.pending = buffer[cut..].to_string();
+                    break;
+                }
+            }
+        }
+
+        (content, thinking)
+    }
+
+    /// Flushes any held-back text at the end of the stream. Returns (content, thinking).
+    pub fn finish(&mut self) -> (String, String) {
+        let rest = std::mem::take(&mut self.pending);
+        if self.in_think {
+            (String::new(), rest)
+        } else {
+            (rest, String::new())
+        }
+    }
+}
+
+/// Removes inline `<think>...</think>` blocks from a complete reply and returns only the reply content. Used for one-shot completions.
+pub fn strip_think_blocks(text: &str) -> String {
+    let mut splitter = ThinkSplitter::default();
+    let (mut content, thinking) = splitter.push(text);
+    let (rest, rest_thinking) = splitter.finish();
+    content.push_str(&rest);
+    // Text after a closing </think> tag usually starts with blank lines
+    if thinking.is_empty() && rest_thinking.is_empty() {
+        content
+    } else {
+        content.trim_start().to_string()
+    }
+}
+
+/// Returns true if Ollama reports the "thinking" capability for a model. Treated as false when the model details can't be fetched.
+pub async fn model_supports_thinking(model: &str) -> bool {
+    show_model_details(model.to_string())
+        .await
+        .ok()
+        .and_then(|details| details.capabilities)
+        .is_some_and(|caps| caps.iter().any(|c| c == "thinking"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds chunks through a splitter and returns everything it emitted, including the final flush.
+    fn split(chunks: &[&str]) -> (String, String) {
+        let mut splitter = ThinkSplitter::default();
+        let mut content = String::new();
+        let mut thinking = String::new();
+        for chunk in chunks {
+            let (c, t) = splitter.push(chunk);
+            content.push_str(&c);
+            thinking.push_str(&t);
+        }
+        let (c, t) = splitter.finish();
+        content.push_str(&c);
+        thinking.push_str(&t);
+        (content, thinking)
+    }
+
+    #[test]
+    fn partial_tag_len_matches_tag_prefixes_only() {
+        assert_eq!(partial_tag_len("hello <th", OPEN_TAG), 3);
+        assert_eq!(partial_tag_len("hello <", OPEN_TAG), 1);
+        assert_eq!(partial_tag_len("hello", OPEN_TAG), 0);
+        assert_eq!(partial_tag_len("done </think", CLOSE_TAG), 7);
+        // A complete tag is not a partial one
+        assert_eq!(partial_tag_len("<think>", OPEN_TAG), 0);
+    }
+
+    #[test]
+    fn splitter_passes_text_without_tags_through() {
+        let mut splitter = ThinkSplitter::default();
+        assert_eq!(
+            splitter.push("just a reply"),
+            ("just a reply".to_string(), String::new())
+        );
+        assert_eq!(splitter.finish(), (String::new(), String::new()));
+    }
+
+    #[test]
+    fn splitter_separates_a_think_block() {
+        assert_eq!(
+            split(&["<think>reasoning</think>answer"]),
+            ("answer".to_string(), "reasoning".to_string())
+        );
+    }
+
+    #[test]
+    fn splitter_handles_tags_split_across_chunks() {
+        assert_eq!(
+            split(&["<th", "ink>rea", "soning</", "think", ">ans", "wer"]),
+            ("answer".to_string(), "reasoning".to_string())
+        );
+    }
+
+    #[test]
+    fn splitter_holds_back_a_possible_tag_until_it_is_resolved() {
+        let mut splitter = ThinkSplitter::default();
+        assert_eq!(splitter.push("a <thi"), ("a ".to_string(), String::new()));
+        // Not a tag after all, so the held-back text is released as content
+        assert_eq!(splitter.push("s>"), ("<this>".to_string(), String::new()));
+    }
+
+    #[test]
+    fn splitter_flushes_an_unclosed_block_as_thinking() {
+        assert_eq!(
+            split(&["before<think>still thinking </thi"]),
+            ("before".to_string(), "still thinking </thi".to_string())
+        );
+    }
+
+    #[test]
+    fn strip_think_blocks_keeps_text_without_tags_unchanged() {
+        assert_eq!(strip_think_blocks("  plain reply\n"), "  plain reply\n");
+    }
+
+    #[test]
+    fn strip_think_blocks_removes_blocks_and_leading_blank_lines() {
+        assert_eq!(
+            strip_think_blocks("<think>plan</think>\n\nReply <think>more</think>text"),
+            "Reply text"
+        );
+    }
+
+    #[test]
+    fn strip_think_blocks_drops_an_unclosed_block() {
+        assert_eq!(strip_think_blocks("Reply<think>never closed"), "Reply");
+    }
+}
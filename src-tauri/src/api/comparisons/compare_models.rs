@@ -0,0 +1,361 @@
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::Emitter;
+
+use super::comparison_storage::{save_comparison, Comparison, ComparisonMetrics, ComparisonResult};
+use crate::api::chats::chat_storage::now_iso;
+use crate::api::chats::think_parser::{model_supports_thinking, ThinkSplitter};
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// Most models a single comparison may fan out to.
+const MAX_COMPARED_MODELS: usize = 6;
+
+/// Request body sent to Ollama's /api/chat endpoint for each compared model.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaCompareRequest {
+    model: String,
+    messages: Vec<OllamaCompareMessage>,
+    stream: bool,
+}
+
+/// Represents a single message in the Ollama chat format.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaCompareMessage {
+    role: String,
+    content: String,
+}
+
+/// Represents a single chunk in Ollama's streaming /api/chat response. Durations are in nanoseconds.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaCompareChunk {
+    message: Option<OllamaCompareChunkMessage>,
+    done: bool,
+    done_reason: Option<String>,
+    total_duration: Option<u64>,
+    load_duration: Option<u64>,
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+    eval_duration: Option<u64>,
+}
+
+/// Represents the message field within a stream chunk.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaCompareChunkMessage {
+    content: Option<String>,
+    thinking: Option<String>,
+}
+
+/// Event emitted to frontend while a compared model streams its reply. Tagged with the model so each column can follow its own stream; the final event carries the metrics.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComparisonStreamEvent {
+    pub comparison_id: String,
+    pub model: String,
+    pub content: String,
+    pub thinking: String,
+    pub done: bool,
+    pub done_reason: Option<String>,
+    pub metrics: Option<ComparisonMetrics>,
+}
+
+/// Error event emitted to frontend when one compared model fails. The other models carry on.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComparisonStreamError {
+    pub comparison_id: String,
+    pub model: String,
+    pub error: String,
+}
+
+/// Event emitted to frontend once every model has finished and the comparison has been saved.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComparisonCompleteEvent {
+    pub comparison_id: String,
+}
+
+/// Converts a duration in nanoseconds to whole milliseconds.
+fn nanos_to_ms(nanos: Option<u64>) -> Option<u64> {
+    nanos.map(|n| n / 1_000_000)
+}
+
+/// Builds the metrics for a reply from Ollama's final chunk.
+fn chunk_metrics(
+    chunk: &OllamaCompareChunk,
+    time_to_first_token_ms: Option<u64>,
+) -> ComparisonMetrics {
+    let tokens_per_second = match (chunk.eval_count, chunk.eval_duration) {
+        (Some(count), Some(duration)) if duration > 0 => {
+            Some(count as f64 / (duration as f64 / 1_000_000_000.0))
+        }
+        _ => None,
+    };
+    ComparisonMetrics {
+        time_to_first_token_ms,
+        total_duration_ms: nanos_to_ms(chunk.total_duration),
+        load_duration_ms: nanos_to_ms(chunk.load_duration),
+        prompt_eval_count: chunk.prompt_eval_count,
+        eval_count: chunk.eval_count,
+        tokens_per_second,
+    }
+}
+
+/// Returns an empty result for a model that has not replied yet.
+fn pending_result(model: &str) -> ComparisonResult {
+    ComparisonResult {
+        model: model.to_string(),
+        content: String::new(),
+        thinking: None,
+        done: false,
+        done_reason: None,
+        error: None,
+        metrics: ComparisonMetrics::default(),
+        promoted_chat_id: None,
+    }
+}
+
+/// Streams one model's reply to the prompt, emitting `comparison-stream-chunk` events, and returns the finished result. Errors are recorded on the result rather than returned.
+async fn run_model(
+    app: &tauri::AppHandle,
+    comparison_id: &str,
+    model: String,
+    prompt: &str,
+) -> ComparisonResult {
+    let mut result = pending_result(&model);
+
+    let emit_error = |result: &mut ComparisonResult, error: String| {
+        let _ = app.emit(
+            "comparison-stream-error",
+            ComparisonStreamError {
+                comparison_id: comparison_id.to_string(),
+                model: result.model.clone(),
+                error: error.clone(),
+            },
+        );
+        result.error = Some(error);
+    };
+
+    let request_body = OllamaCompareRequest {
+        model: model.clone(),
+        messages: vec![OllamaCompareMessage {
+            role: "user".to_string(),
+            content: prompt.to_string(),
+        }],
+        stream: true,
+    };
+
+    // Only reasoning models get inline <think> blocks split off, so other models keep a literal "<think>"
+    let split_inline_think = model_supports_thinking(&model).await;
+
+    let started = Instant::now();
+    let client = reqwest::Client::new();
+    let response = match client
+        .post("http://localhost:11434/api/chat")
+        .json(&request_body)
+        .send()
+        .await
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            let error_msg = if e.is_connect() {
+                "Could not connect to Ollama. Make sure Ollama is running on http://localhost:11434"
+                    .to_string()
+            } else if e.is_timeout() {
+                format!("Request to Ollama timed out for model '{}'", model)
+            } else {
+                format!("Network error for model '{}': {}", model, e)
+            };
+            emit_error(&mut result, error_msg);
+            return result;
+        }
+    };
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let error_msg = match status.as_u16() {
+            404 => format!("Model '{}' not found. Pull it first", model),
+            _ => format!(
+                "Ollama returned HTTP {} for model '{}': {}",
+                status, model, body
+            ),
+        };
+        emit_error(&mut result, error_msg);
+        return result;
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut buffer: Vec<u8> = vec![];
+    let mut splitter = split_inline_think.then(ThinkSplitter::default);
+    let mut thinking = String::new();
+    let mut time_to_first_token_ms = None;
+
+    'stream: while let Some(chunk_result) = stream.next().await {
+        let bytes = match chunk_result {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                emit_error(&mut result, format!("Stream error: {}", e));
+                break;
+            }
+        };
+        buffer.extend_from_slice(&bytes);
+
+        // Process complete lines (NDJSON - newline-delimited JSON)
+        while let Some(newline_pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line_bytes: Vec<u8> = buffer.drain(..=newline_pos).collect();
+            let line = String::from_utf8_lossy(&line_bytes).trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+
+            let chunk = match serde_json::from_str::<OllamaCompareChunk>(&line) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    eprintln!("Failed to parse comparison chunk: {} - line: {}", e, line);
+                    continue;
+                }
+            };
+
+            let message = chunk.message.as_ref();
+            let raw = message.and_then(|m| m.content.clone()).unwrap_or_default();
+            let mut chunk_thinking = message.and_then(|m| m.thinking.clone()).unwrap_or_default();
+            let mut content = raw;
+            if let Some(ref mut splitter) = splitter {
+                let (text, inline_thinking) = splitter.push(&content);
+                content = text;
+                chunk_thinking.push_str(&inline_thinking);
+                if chunk.done {
+                    let (rest, rest_thinking) = splitter.finish();
+                    content.push_str(&rest);
+                    chunk_thinking.push_str(&rest_thinking);
+                }
+            }
+
+            if time_to_first_token_ms.is_none()
+                && !(content.is_empty() && chunk_thinking.is_empty())
+            {
+                time_to_first_token_ms = Some(started.elapsed().as_millis() as u64);
+            }
+            result.content.push_str(&content);
+            thinking.push_str(&chunk_thinking);
+
+            let metrics = chunk
+                .done
+                .then(|| chunk_metrics(&chunk, time_to_first_token_ms));
+            let _ = app.emit(
+                "comparison-stream-chunk",
+                ComparisonStreamEvent {
+                    comparison_id: comparison_id.to_string(),
+                    model: model.clone(),
+                    content,
+                    thinking: chunk_thinking,
+                    done: chunk.done,
+                    done_reason: chunk.done_reason.clone(),
+                    metrics: metrics.clone(),
+                },
+            );
+
+            if let Some(metrics) = metrics {
+                result.done = true;
+                result.done_reason = chunk.done_reason;
+                result.metrics = metrics;
+                break 'stream;
+            }
+        }
+    }
+
+    if !result.done && result.error.is_none() {
+        emit_error(
+            &mut result,
+            format!("Model '{}' stopped before finishing its reply", model),
+        );
+    }
+
+    let thinking = thinking.trim();
+    if !thinking.is_empty() {
+        result.thinking = Some(thinking.to_string());
+        result.content = result.content.trim_start().to_string();
+    }
+    result
+}
+
+/// Tauri command: Sends the same prompt to several models and streams each reply on `comparison-stream-chunk` events tagged with the model.
+/// Models run concurrently, or one after another when `sequential` is true (useful when memory only fits one model). Returns the comparison ID;
+/// the record with per-model metrics is saved when all models finish and `comparison-complete` is emitted.
+#[tauri::command]
+pub async fn compare_models(
+    app: tauri::AppHandle,
+    models: Vec<String>,
+    message: String,
+    workspace_id: Option<String>,
+    sequential: Option<bool>,
+) -> Result<String, String> {
+    let mut unique_models: Vec<String> = vec![];
+    for model in models {
+        let model = model.trim().to_string();
+        if !model.is_empty() && !unique_models.contains(&model) {
+            unique_models.push(model);
+        }
+    }
+    if unique_models.len() < 2 {
+        return Err("Select at least two different models to compare".to_string());
+    }
+    if unique_models.len() > MAX_COMPARED_MODELS {
+        return Err(format!(
+            "Cannot compare more than {} models at once",
+            MAX_COMPARED_MODELS
+        ));
+    }
+    if message.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+
+    // Resolve workspace_id: use provided, or fall back to active workspace
+    let workspace_id = match workspace_id {
+        Some(id) if !id.is_empty() => id,
+        _ => load_workspaces_index()?.active_workspace_id,
+    };
+    let sequential = sequential.unwrap_or(false);
+
+    let mut comparison = Comparison {
+        id: uuid::Uuid::new_v4().to_string(),
+        workspace_id,
+        prompt: message,
+        sequential,
+        results: unique_models.iter().map(|m| pending_result(m)).collect(),
+        created_at: now_iso(),
+        completed_at: None,
+    };
+    save_comparison(&comparison)?;
+
+    let comparison_id = comparison.id.clone();
+
+    // Spawn a background task to run the models
+    tauri::async_runtime::spawn(async move {
+        let prompt = comparison.prompt.clone();
+        let results = if comparison.sequential {
+            let mut results = vec![];
+            for model in unique_models {
+                results.push(run_model(&app, &comparison.id, model, &prompt).await);
+            }
+            results
+        } else {
+            let runs = unique_models
+                .into_iter()
+                .map(|model| run_model(&app, &comparison.id, model, &prompt));
+            futures_util::future::join_all(runs).await
+        };
+
+        comparison.results = results;
+        comparison.completed_at = Some(now_iso());
+        if let Err(e) = save_comparison(&comparison) {
+            eprintln!("Failed to save comparison {}: {}", comparison.id, e);
+        }
+        let _ = app.emit(
+            "comparison-complete",
+            ComparisonCompleteEvent {
+                comparison_id: comparison.id.clone(),
+            },
+        );
+    });
+
+    Ok(comparison_id)
+}
@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::api::chats::chat_storage::{
+    create_new_chat, get_chat_meta, load_chats_index, record_reply_model, save_chat_data, ChatData,
+    ChatMessage, ChatMeta,
+};
+
+/// Timing and token counts reported by Ollama for one model's reply, plus the time to the first streamed token measured locally.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ComparisonMetrics {
+    pub time_to_first_token_ms: Option<u64>,
+    pub total_duration_ms: Option<u64>,
+    pub load_duration_ms: Option<u64>,
+    pub prompt_eval_count: Option<u64>,
+    pub eval_count: Option<u64>,
+    pub tokens_per_second: Option<f64>,
+}
+
+/// One model's reply within a comparison. `error` is set if the model failed; `promoted_chat_id` once the reply was turned into a chat.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComparisonResult {
+    pub model: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    pub done: bool,
+    pub done_reason: Option<String>,
+    pub error: Option<String>,
+    pub metrics: ComparisonMetrics,
+    pub promoted_chat_id: Option<String>,
+}
+
+/// A prompt sent to several models and their replies. Stored in individual JSON files next to the chats.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Comparison {
+    pub id: String,
+    pub workspace_id: String,
+    pub prompt: String,
+    pub sequential: bool,
+    pub results: Vec<ComparisonResult>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// Metadata for a comparison stored in the comparisons index.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComparisonMeta {
+    pub id: String,
+    pub workspace_id: String,
+    pub prompt: String,
+    pub models: Vec<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// The root structure for the comparisons index file (comparisons_index.json).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComparisonsIndex {
+    pub comparisons: Vec<ComparisonMeta>,
+}
+
+/// Returns the path to the .data directory, creating it and the comparisons subdirectory if they don't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    let comparisons_dir = data_dir.join("comparisons");
+    if !comparisons_dir.exists() {
+        fs::create_dir_all(&comparisons_dir)
+            .map_err(|e| format!("Failed to create .data/comparisons directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the comparisons_index.json file.
+fn get_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("comparisons_index.json"))
+}
+
+/// Returns the file path for a comparison record.
+fn get_comparison_file_path(comparison_id: &str) -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir
+        .join("comparisons")
+        .join(format!("{}.json", comparison_id)))
+}
+
+/// Returns the index entry describing a comparison.
+fn comparison_meta(comparison: &Comparison) -> ComparisonMeta {
+    ComparisonMeta {
+        id: comparison.id.clone(),
+        workspace_id: comparison.workspace_id.clone(),
+        prompt: comparison.prompt.clone(),
+        models: comparison.results.iter().map(|r| r.model.clone()).collect(),
+        created_at: comparison.created_at.clone(),
+        completed_at: comparison.completed_at.clone(),
+    }
+}
+
+/// Loads the comparisons index, creating it with an empty list if it doesn't exist.
+fn load_comparisons_index() -> Result<ComparisonsIndex, String> {
+    let index_path = get_index_path()?;
+    if !index_path.exists() {
+        let index = ComparisonsIndex {
+            comparisons: vec![],
+        };
+        save_comparisons_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read comparisons index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse comparisons index: {}", e))
+}
+
+/// Saves the comparisons index to comparisons_index.json.
+fn save_comparisons_index(index: &ComparisonsIndex) -> Result<(), String> {
+    let index_path = get_index_path()?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize comparisons index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write comparisons index: {}", e))
+}
+
+/// Loads a comparison record.
+pub fn load_comparison(comparison_id: &str) -> Result<Comparison, String> {
+    let path = get_comparison_file_path(comparison_id)?;
+    if !path.exists() {
+        return Err(format!("Comparison with id '{}' not found", comparison_id));
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read comparison: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse comparison: {}", e))
+}
+
+/// Writes a comparison record and refreshes its index entry, adding it if it is new. Used when a comparison starts, finishes and when a result is promoted.
+pub fn save_comparison(comparison: &Comparison) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(comparison)
+        .map_err(|e| format!("Failed to serialize comparison: {}", e))?;
+    fs::write(get_comparison_file_path(&comparison.id)?, content)
+        .map_err(|e| format!("Failed to write comparison: {}", e))?;
+
+    let mut index = load_comparisons_index()?;
+    let meta = comparison_meta(comparison);
+    match index.comparisons.iter_mut().find(|c| c.id == comparison.id) {
+        Some(existing) => *existing = meta,
+        None => index.comparisons.push(meta),
+    }
+    save_comparisons_index(&index)
+}
+
+/// Tauri command: Returns all comparisons in a workspace. Called from frontend to list comparisons next to chats.
+#[tauri::command]
+pub async fn list_comparisons(workspace_id: String) -> Result<Vec<ComparisonMeta>, String> {
+    Ok(load_comparisons_index()?
+        .comparisons
+        .into_iter()
+        .filter(|c| c.workspace_id == workspace_id)
+        .collect())
+}
+
+/// Tauri command: Returns a comparison with every model's reply and metrics. Called from frontend comparison view.
+#[tauri::command]
+pub async fn get_comparison(comparison_id: String) -> Result<Comparison, String> {
+    load_comparison(&comparison_id)
+}
+
+/// Tauri command: Deletes a comparison record. Chats promoted from it are kept. Called from frontend comparison view.
+#[tauri::command]
+pub async fn delete_comparison(comparison_id: String) -> Result<(), String> {
+    let mut index = load_comparisons_index()?;
+    let before = index.comparisons.len();
+    index.comparisons.retain(|c| c.id != comparison_id);
+    if index.comparisons.len() == before {
+        return Err(format!("Comparison with id '{}' not found", comparison_id));
+    }
+
+    let path = get_comparison_file_path(&comparison_id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete comparison: {}", e))?;
+    }
+    save_comparisons_index(&index)
+}
+
+/// Tauri command: Turns one model's reply into a normal chat containing the prompt and that reply, in the comparison's workspace. A reply is only promoted once. Called from frontend comparison view.
+#[tauri::command]
+pub async fn promote_comparison_result(
+    comparison_id: String,
+    model: String,
+) -> Result<ChatMeta, String> {
+    let mut comparison = load_comparison(&comparison_id)?;
+    let prompt = comparison.prompt.clone();
+    let workspace_id = comparison.workspace_id.clone();
+    let created_at = comparison.created_at.clone();
+
+    let result = comparison
+        .results
+        .iter_mut()
+        .find(|r| r.model == model)
+        .ok_or_else(|| format!("Model '{}' is not part of this comparison", model))?;
+    if !result.done || result.error.is_some() {
+        return Err(format!(
+            "Model '{}' has no completed reply to promote",
+            model
+        ));
+    }
+    // Promoting twice returns the chat made the first time
+    if let Some(chat_id) = result.promoted_chat_id.as_deref() {
+        return load_chats_index()?
+            .chats
+            .into_iter()
+            .find(|c| c.id == chat_id)
+            .ok_or_else(|| {
+                format!(
+                    "Model '{}' was already promoted to a chat that has since been deleted",
+                    model
+                )
+            });
+    }
+
+    let meta = create_new_chat(&model, &prompt, &workspace_id, None, None)?;
+    let data = ChatData {
+        messages: vec![
+            ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+                created_at: Some(created_at),
+                ..Default::default()
+            },
+            ChatMessage {
+                role: "assistant".to_string(),
+                content: result.content.clone(),
+                created_at: comparison.completed_at.clone(),
+                thinking: result.thinking.clone(),
+                model: Some(model.clone()),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    save_chat_data(&meta.id, &data)?;
+    record_reply_model(&meta.id, &model)?;
+
+    result.promoted_chat_id = Some(meta.id.clone());
+    save_comparison(&comparison)?;
+
+    get_chat_meta(&meta.id)
+}
@@ -0,0 +1,2 @@
+pub mod compare_models;
+pub mod comparison_storage;
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// The parameters of a raw /api/generate request, as sent from the completion playground and stored with each run.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CompletionParams {
+    pub prompt: String,
+    pub suffix: Option<String>,
+    pub system: Option<String>,
+    pub template: Option<String>,
+    pub raw: bool,
+    pub context: Option<Vec<i64>>,
+    pub options: Option<serde_json::Value>,
+}
+
+/// A single generate request and its output. `context` is Ollama's encoding of the conversation so far and can be passed to the next request to continue it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionRun {
+    pub id: String,
+    pub model: String,
+    pub params: CompletionParams,
+    pub response: String,
+    pub done_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<i64>,
+    pub prompt_eval_count: Option<u64>,
+    pub eval_count: Option<u64>,
+    pub created_at: String,
+}
+
+/// Contains the runs of a completion session. Stored in individual JSON files under .data/completions.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CompletionSessionData {
+    pub runs: Vec<CompletionRun>,
+}
+
+/// Metadata for a completion session stored in the completions index. Kept separate from chats.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionSessionMeta {
+    pub id: String,
+    pub title: String,
+    pub model: String,
+    pub run_count: usize,
+    pub created_at: String,
+    pub last_updated_at: String,
+}
+
+/// The root structure for the completions index file (completions_index.json).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionsIndex {
+    pub sessions: Vec<CompletionSessionMeta>,
+}
+
+/// A session's metadata together with its runs. Returned to the frontend when opening a session.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionSession {
+    pub meta: CompletionSessionMeta,
+    pub runs: Vec<CompletionRun>,
+}
+
+/// Returns the path to the .data directory, creating it and the completions subdirectory if they don't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    let completions_dir = data_dir.join("completions");
+    if !completions_dir.exists() {
+        fs::create_dir_all(&completions_dir)
+            .map_err(|e| format!("Failed to create .data/completions directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the completions_index.json file.
+fn get_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("completions_index.json"))
+}
+
+/// Returns the file path for a completion session's runs.
+fn get_session_file_path(session_id: &str) -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir
+        .join("completions")
+        .join(format!("{}.json", session_id)))
+}
+
+/// Returns the current UTC time as an ISO 8601 RFC3339 string.
+pub fn now_iso() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Loads the completions index, creating it with an empty list if it doesn't exist.
+pub fn load_completions_index() -> Result<CompletionsIndex, String> {
+    let index_path = get_index_path()?;
+    if !index_path.exists() {
+        let index = CompletionsIndex { sessions: vec![] };
+        save_completions_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read completions index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse completions index: {}", e))
+}
+
+/// Saves the completions index to completions_index.json.
+pub fn save_completions_index(index: &CompletionsIndex) -> Result<(), String> {
+    let index_path = get_index_path()?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize completions index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write completions index: {}", e))
+}
+
+/// Loads the runs of a completion session, or an empty session if its file doesn't exist yet.
+pub fn load_session_data(session_id: &str) -> Result<CompletionSessionData, String> {
+    let path = get_session_file_path(session_id)?;
+    if !path.exists() {
+        return Ok(CompletionSessionData::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read completion session: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse completion session: {}", e))
+}
+
+/// Saves the runs of a completion session.
+fn save_session_data(session_id: &str, data: &CompletionSessionData) -> Result<(), String> {
+    let path = get_session_file_path(session_id)?;
+    let content = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("Failed to serialize completion session: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write completion session: {}", e))
+}
+
+/// Creates an empty completion session titled after the first prompt. Used by generate_completion when no session is given.
+pub fn create_session(model: &str, prompt: &str) -> Result<CompletionSessionMeta, String> {
+    let first_line = prompt.trim().lines().next().unwrap_or_default();
+    let title = if first_line.chars().count() > 50 {
+        format!("{}...", first_line.chars().take(50).collect::<String>())
+    } else if first_line.is_empty() {
+        "Untitled completion".to_string()
+    } else {
+        first_line.to_string()
+    };
+
+    let now = now_iso();
+    let meta = CompletionSessionMeta {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        model: model.to_string(),
+        run_count: 0,
+        created_at: now.clone(),
+        last_updated_at: now,
+    };
+
+    save_session_data(&meta.id, &CompletionSessionData::default())?;
+    let mut index = load_completions_index()?;
+    index.sessions.push(meta.clone());
+    save_completions_index(&index)?;
+    Ok(meta)
+}
+
+/// Appends a finished run to a session and updates the session's metadata. Used by generate_completion once the stream is done.
+pub fn append_run(session_id: &str, run: CompletionRun) -> Result<(), String> {
+    let mut index = load_completions_index()?;
+    let meta = index
+        .sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| format!("Completion session with id '{}' not found", session_id))?;
+
+    let mut data = load_session_data(session_id)?;
+    meta.model = run.model.clone();
+    meta.last_updated_at = run.created_at.clone();
+    data.runs.push(run);
+    meta.run_count = data.runs.len();
+
+    save_session_data(session_id, &data)?;
+    save_completions_index(&index)
+}
+
+/// Tauri command: Returns all completion sessions. Called from frontend completion playground sidebar.
+#[tauri::command]
+pub async fn list_completion_sessions() -> Result<Vec<CompletionSessionMeta>, String> {
+    Ok(load_completions_index()?.sessions)
+}
+
+/// Tauri command: Returns a completion session with all its runs. Called from frontend when opening a session in the playground.
+#[tauri::command]
+pub async fn get_completion_session(session_id: String) -> Result<CompletionSession, String> {
+    let meta = load_completions_index()?
+        .sessions
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| format!("Completion session with id '{}' not found", session_id))?;
+    let data = load_session_data(&session_id)?;
+    Ok(CompletionSession {
+        meta,
+        runs: data.runs,
+    })
+}
+
+/// Tauri command: Renames a completion session. Called from frontend when user edits a session title.
+#[tauri::command]
+pub async fn rename_completion_session(
+    session_id: String,
+    new_title: String,
+) -> Result<(), String> {
+    let trimmed = new_title.trim();
+    if trimmed.is_empty() {
+        return Err("Session title cannot be empty".to_string());
+    }
+
+    let mut index = load_completions_index()?;
+    let session = index
+        .sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| format!("Completion session with id '{}' not found", session_id))?;
+    session.title = trimmed.to_string();
+    session.last_updated_at = now_iso();
+    save_completions_index(&index)
+}
+
+/// Tauri command: Deletes a completion session and its runs. Called from frontend completion playground.
+#[tauri::command]
+pub async fn delete_completion_session(session_id: String) -> Result<(), String> {
+    let mut index = load_completions_index()?;
+    let before = index.sessions.len();
+    index.sessions.retain(|s| s.id != session_id);
+    if index.sessions.len() == before {
+        return Err(format!(
+            "Completion session with id '{}' not found",
+            session_id
+        ));
+    }
+
+    let path = get_session_file_path(&session_id)?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to delete completion session file: {}", e))?;
+    }
+    save_completions_index(&index)
+}
@@ -0,0 +1,269 @@
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use super::completion_storage::{
+    append_run, create_session, load_completions_index, now_iso, CompletionParams, CompletionRun,
+};
+
+/// Request body sent to Ollama's /api/generate endpoint. Optional fields are left out so Ollama applies the model's defaults.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaGenerateRequest {
+    model: String,
+    prompt: String,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suffix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    raw: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<serde_json::Value>,
+}
+
+/// Represents a single chunk in Ollama's /api/generate streaming response (NDJSON format). The final chunk carries the context and token counts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaGenerateChunk {
+    response: Option<String>,
+    done: bool,
+    done_reason: Option<String>,
+    context: Option<Vec<i64>>,
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+}
+
+/// Event emitted to frontend during a streaming completion. The final event carries the context that continues the session.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionStreamEvent {
+    pub session_id: String,
+    pub content: String,
+    pub done: bool,
+    pub done_reason: Option<String>,
+    pub context: Option<Vec<i64>>,
+}
+
+/// Error event emitted to frontend when a completion request fails.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionStreamError {
+    pub session_id: String,
+    pub error: String,
+}
+
+/// Returns None for missing or blank optional text fields so they are not sent to Ollama.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// Tauri command: Runs a raw completion through Ollama's /api/generate and streams the tokens to the frontend as `completion-stream-chunk` events.
+/// Supports `suffix` (fill-in-the-middle), `system`, `template`, `raw` and a `context` from a previous run. Creates a new session if session_id is None and returns the session ID.
+#[tauri::command]
+pub async fn generate_completion(
+    app: tauri::AppHandle,
+    session_id: Option<String>,
+    model: String,
+    params: CompletionParams,
+) -> Result<String, String> {
+    let params = CompletionParams {
+        suffix: non_empty(params.suffix),
+        system: non_empty(params.system),
+        template: non_empty(params.template),
+        context: params.context.filter(|c| !c.is_empty()),
+        ..params
+    };
+
+    // Whitespace is kept in what is sent, but a blank prompt and suffix leave nothing to complete
+    if params.prompt.trim().is_empty() && params.suffix.is_none() {
+        return Err("Prompt cannot be empty".to_string());
+    }
+
+    let session_id = match session_id {
+        Some(id) => {
+            let exists = load_completions_index()?
+                .sessions
+                .iter()
+                .any(|s| s.id == id);
+            if !exists {
+                return Err(format!("Completion session with id '{}' not found", id));
+            }
+            id
+        }
+        None => create_session(&model, &params.prompt)?.id,
+    };
+
+    let request_body = OllamaGenerateRequest {
+        model: model.clone(),
+        prompt: params.prompt.clone(),
+        stream: true,
+        suffix: params.suffix.clone(),
+        system: params.system.clone(),
+        template: params.template.clone(),
+        raw: params.raw,
+        context: params.context.clone(),
+        options: params.options.clone(),
+    };
+
+    let session_id_for_task = session_id.clone();
+
+    // Spawn a background task to handle streaming
+    tauri::async_runtime::spawn(async move {
+        let client = reqwest::Client::new();
+
+        let response = match client
+            .post("http://localhost:11434/api/generate")
+            .json(&request_body)
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                let error_msg = if e.is_connect() {
+                    "Could not connect to Ollama. Make sure Ollama is running on http://localhost:11434".to_string()
+                } else if e.is_timeout() {
+                    "Request to Ollama timed out".to_string()
+                } else {
+                    format!("Network error: {}", e)
+                };
+
+                let _ = app.emit(
+                    "completion-stream-error",
+                    CompletionStreamError {
+                        session_id: session_id_for_task.clone(),
+                        error: error_msg,
+                    },
+                );
+                return;
+            }
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let _ = app.emit(
+                "completion-stream-error",
+                CompletionStreamError {
+                    session_id: session_id_for_task.clone(),
+                    error: format!("Ollama returned HTTP {}: {}", status, body),
+                },
+            );
+            return;
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut full_response = String::new();
+        let mut buffer: Vec<u8> = vec![];
+        let mut final_chunk: Option<OllamaGenerateChunk> = None;
+
+        while let Some(chunk_result) = stream.next().await {
+            match chunk_result {
+                Ok(bytes) => {
+                    // Buffer raw bytes so characters split across network chunks decode correctly
+                    buffer.extend_from_slice(&bytes);
+
+                    // Process complete lines (NDJSON - newline-delimited JSON)
+                    while let Some(newline_pos) = buffer.iter().position(|&b| b == b'\n') {
+                        let line_bytes: Vec<u8> = buffer.drain(..=newline_pos).collect();
+                        let line = String::from_utf8_lossy(&line_bytes).trim().to_string();
+
+                        if line.is_empty() {
+                            continue;
+                        }
+
+                        match serde_json::from_str::<OllamaGenerateChunk>(&line) {
+                            Ok(chunk) => {
+                                let content = chunk.response.clone().unwrap_or_default();
+                                full_response.push_str(&content);
+
+                                let _ = app.emit(
+                                    "completion-stream-chunk",
+                                    CompletionStreamEvent {
+                                        session_id: session_id_for_task.clone(),
+                                        content,
+                                        done: chunk.done,
+                                        done_reason: chunk.done_reason.clone(),
+                                        context: chunk.context.clone(),
+                                    },
+                                );
+
+                                if chunk.done {
+                                    final_chunk = Some(chunk);
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!(
+                                    "Failed to parse completion chunk: {} - line: {}",
+                                    e, line
+                                );
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    let _ = app.emit(
+                        "completion-stream-error",
+                        CompletionStreamError {
+                            session_id: session_id_for_task.clone(),
+                            error: format!("Stream error: {}", e),
+                        },
+                    );
+                    break;
+                }
+            }
+        }
+
+        // Handle any remaining data in the buffer
+        let remaining = String::from_utf8_lossy(&buffer).trim().to_string();
+        if !remaining.is_empty() {
+            if let Ok(chunk) = serde_json::from_str::<OllamaGenerateChunk>(&remaining) {
+                let content = chunk.response.clone().unwrap_or_default();
+                full_response.push_str(&content);
+
+                let _ = app.emit(
+                    "completion-stream-chunk",
+                    CompletionStreamEvent {
+                        session_id: session_id_for_task.clone(),
+                        content,
+                        done: chunk.done,
+                        done_reason: chunk.done_reason.clone(),
+                        context: chunk.context.clone(),
+                    },
+                );
+
+                if chunk.done {
+                    final_chunk = Some(chunk);
+                }
+            }
+        }
+
+        let Some(last) = final_chunk else {
+            return;
+        };
+
+        let run = CompletionRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            model: request_body.model,
+            params,
+            response: full_response,
+            done_reason: last.done_reason,
+            context: last.context.unwrap_or_default(),
+            prompt_eval_count: last.prompt_eval_count,
+            eval_count: last.eval_count,
+            created_at: now_iso(),
+        };
+        if let Err(e) = append_run(&session_id_for_task, run) {
+            let _ = app.emit(
+                "completion-stream-error",
+                CompletionStreamError {
+                    session_id: session_id_for_task.clone(),
+                    error: e,
+                },
+            );
+        }
+    });
+
+    Ok(session_id)
+}
@@ -0,0 +1,2 @@
+pub mod completion_storage;
+pub mod generate_completion;
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+/// Request body sent to Ollama's /api/embed endpoint. Optional fields are omitted so Ollama applies its own defaults.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaEmbedRequest {
+    model: String,
+    input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    truncate: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+}
+
+/// Response from Ollama's /api/embed endpoint containing one vector per input.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaEmbedResponse {
+    model: String,
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Embeds a batch of inputs with the given model through Ollama's /api/embed endpoint. Used by semantic search, RAG indexing and the embeddings playground.
+pub async fn embed(
+    model: &str,
+    inputs: Vec<String>,
+    truncate: Option<bool>,
+    dimensions: Option<u32>,
+) -> Result<Vec<Vec<f32>>, String> {
+    if inputs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let client = reqwest::Client::new();
+    let input_count = inputs.len();
+
+    let body = OllamaEmbedRequest {
+        model: model.to_string(),
+        input: inputs,
+        truncate,
+        dimensions,
+    };
+
+    let response = client
+        .post("http://localhost:11434/api/embed")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_connect() {
+                "Could not connect to Ollama. Make sure Ollama is running on http://localhost:11434"
+                    .to_string()
+            } else if e.is_timeout() {
+                format!(
+                    "Request to Ollama timed out while embedding with model '{}'",
+                    model
+                )
+            } else {
+                format!(
+                    "Network error while embedding with model '{}': {}",
+                    model, e
+                )
+            }
+        })?;
+
+    let status = response.status();
+
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        let ollama_msg = serde_json::from_str::<serde_json::Value>(&error_body)
+            .ok()
+            .and_then(|v| v["error"].as_str().map(String::from))
+            .unwrap_or(error_body);
+
+        return Err(match status.as_u16() {
+            404 => format!(
+                "Embedding model '{}' not found. Pull it first with 'ollama pull {}'",
+                model, model
+            ),
+            400 => format!("Invalid embedding request for '{}': {}", model, ollama_msg),
+            500 => format!(
+                "Ollama encountered an internal error while embedding with '{}': {}",
+                model, ollama_msg
+            ),
+            _ => format!(
+                "Unexpected error embedding with '{}' (HTTP {}): {}",
+                model, status, ollama_msg
+            ),
+        });
+    }
+
+    let parsed = response.json::<OllamaEmbedResponse>().await.map_err(|e| {
+        format!(
+            "Failed to parse the embed response for model '{}' from Ollama: {}",
+            model, e
+        )
+    })?;
+
+    if parsed.embeddings.len() != input_count {
+        return Err(format!(
+            "Ollama returned {} embeddings for {} inputs with model '{}'",
+            parsed.embeddings.len(),
+            input_count,
+            parsed.model
+        ));
+    }
+
+    Ok(parsed.embeddings)
+}
+
+/// Returns the cosine similarity of two vectors, or 0.0 when either is empty, zero or their lengths differ.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b.iter()) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use super::embed_client::{cosine_similarity, embed};
+
+/// Vectors returned by embed_texts, with the model and vector size they came from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbedTextsResult {
+    pub model: String,
+    pub dimensions: usize,
+    pub embeddings: Vec<Vec<f32>>,
+    pub duration_ms: u64,
+}
+
+/// Returns the pairwise cosine-similarity matrix of the given vectors. The matrix is symmetric with 1.0 on the diagonal for non-zero vectors.
+pub fn similarity_matrix(vectors: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let n = vectors.len();
+    let mut matrix = vec![vec![0.0f32; n]; n];
+    for i in 0..n {
+        for j in i..n {
+            let score = cosine_similarity(&vectors[i], &vectors[j]);
+            matrix[i][j] = score;
+            matrix[j][i] = score;
+        }
+    }
+    matrix
+}
+
+/// Tauri command: Embeds a batch of texts with the given model through Ollama's /api/embed. Called from frontend embeddings playground.
+/// `truncate` controls whether over-long inputs are cut to the model's context; `dimensions` requests shorter vectors from models that support it.
+#[tauri::command]
+pub async fn embed_texts(
+    model: String,
+    inputs: Vec<String>,
+    truncate: Option<bool>,
+    dimensions: Option<u32>,
+) -> Result<EmbedTextsResult, String> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err("Embedding model cannot be empty".to_string());
+    }
+    if inputs.is_empty() {
+        return Err("Provide at least one input to embed".to_string());
+    }
+    if dimensions == Some(0) {
+        return Err("Dimensions must be greater than zero".to_string());
+    }
+
+    let started = std::time::Instant::now();
+    let embeddings = embed(&model, inputs, truncate, dimensions).await?;
+
+    Ok(EmbedTextsResult {
+        model,
+        dimensions: embeddings.first().map_or(0, |v| v.len()),
+        embeddings,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Tauri command: Computes the pairwise cosine-similarity matrix of the given vectors. Called from frontend embeddings playground.
+#[tauri::command]
+pub async fn compute_similarity_matrix(vectors: Vec<Vec<f32>>) -> Result<Vec<Vec<f32>>, String> {
+    if let Some(first) = vectors.first() {
+        if vectors.iter().any(|v| v.len() != first.len()) {
+            return Err("All vectors must have the same number of dimensions".to_string());
+        }
+    }
+    Ok(similarity_matrix(&vectors))
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::embed_texts::similarity_matrix;
+
+/// A named batch of inputs and their vectors, saved so embedding models can be compared later.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingSet {
+    pub id: String,
+    pub name: String,
+    pub model: String,
+    pub truncate: Option<bool>,
+    pub dimensions: Option<u32>,
+    pub inputs: Vec<String>,
+    pub embeddings: Vec<Vec<f32>>,
+    pub created_at: String,
+}
+
+/// Metadata for an embedding set stored in the index, without the vectors.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingSetMeta {
+    pub id: String,
+    pub name: String,
+    pub model: String,
+    pub input_count: usize,
+    pub vector_size: usize,
+    pub created_at: String,
+}
+
+/// The root structure for the embedding sets index file (embedding_sets_index.json).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingSetsIndex {
+    pub sets: Vec<EmbeddingSetMeta>,
+}
+
+/// Returns the path to the .data directory, creating it and the embedding_sets subdirectory if they don't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    let sets_dir = data_dir.join("embedding_sets");
+    if !sets_dir.exists() {
+        fs::create_dir_all(&sets_dir)
+            .map_err(|e| format!("Failed to create .data/embedding_sets directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the embedding_sets_index.json file.
+fn get_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("embedding_sets_index.json"))
+}
+
+/// Returns the file path for a saved embedding set.
+fn get_set_file_path(set_id: &str) -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir
+        .join("embedding_sets")
+        .join(format!("{}.json", set_id)))
+}
+
+/// Loads the embedding sets index, creating it with an empty list if it doesn't exist.
+fn load_sets_index() -> Result<EmbeddingSetsIndex, String> {
+    let index_path = get_index_path()?;
+    if !index_path.exists() {
+        let index = EmbeddingSetsIndex { sets: vec![] };
+        save_sets_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read embedding sets index: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse embedding sets index: {}", e))
+}
+
+/// Saves the embedding sets index.
+fn save_sets_index(index: &EmbeddingSetsIndex) -> Result<(), String> {
+    let index_path = get_index_path()?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize embedding sets index: {}", e))?;
+    fs::write(&index_path, content)
+        .map_err(|e| format!("Failed to write embedding sets index: {}", e))
+}
+
+/// Loads a saved embedding set with its vectors.
+fn load_set(set_id: &str) -> Result<EmbeddingSet, String> {
+    let path = get_set_file_path(set_id)?;
+    if !path.exists() {
+        return Err(format!("Embedding set with id '{}' not found", set_id));
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read embedding set: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse embedding set: {}", e))
+}
+
+/// Tauri command: Saves a batch of inputs and their vectors under a name. Called from frontend embeddings playground after embed_texts.
+#[tauri::command]
+pub async fn save_embedding_set(
+    name: String,
+    model: String,
+    inputs: Vec<String>,
+    embeddings: Vec<Vec<f32>>,
+    truncate: Option<bool>,
+    dimensions: Option<u32>,
+) -> Result<EmbeddingSetMeta, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Embedding set name cannot be empty".to_string());
+    }
+    if inputs.len() != embeddings.len() {
+        return Err(format!(
+            "Got {} vectors for {} inputs",
+            embeddings.len(),
+            inputs.len()
+        ));
+    }
+
+    let set = EmbeddingSet {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: trimmed.to_string(),
+        model,
+        truncate,
+        dimensions,
+        inputs,
+        embeddings,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let meta = EmbeddingSetMeta {
+        id: set.id.clone(),
+        name: set.name.clone(),
+        model: set.model.clone(),
+        input_count: set.inputs.len(),
+        vector_size: set.embeddings.first().map_or(0, |v| v.len()),
+        created_at: set.created_at.clone(),
+    };
+
+    // Vectors are large, so sets are stored compactly
+    let content = serde_json::to_string(&set)
+        .map_err(|e| format!("Failed to serialize embedding set: {}", e))?;
+    fs::write(get_set_file_path(&set.id)?, content)
+        .map_err(|e| format!("Failed to write embedding set: {}", e))?;
+
+    let mut index = load_sets_index()?;
+    index.sets.push(meta.clone());
+    save_sets_index(&index)?;
+
+    Ok(meta)
+}
+
+/// Tauri command: Returns all saved embedding sets without their vectors. Called from frontend embeddings playground.
+#[tauri::command]
+pub async fn list_embedding_sets() -> Result<Vec<EmbeddingSetMeta>, String> {
+    Ok(load_sets_index()?.sets)
+}
+
+/// Tauri command: Returns a saved embedding set with its inputs and vectors. Called from frontend when opening a set.
+#[tauri::command]
+pub async fn get_embedding_set(set_id: String) -> Result<EmbeddingSet, String> {
+    load_set(&set_id)
+}
+
+/// Tauri command: Returns the pairwise cosine-similarity matrix of a saved set's vectors. Called from frontend embeddings playground.
+#[tauri::command]
+pub async fn get_embedding_set_similarity(set_id: String) -> Result<Vec<Vec<f32>>, String> {
+    let set = load_set(&set_id)?;
+    Ok(similarity_matrix(&set.embeddings))
+}
+
+/// Tauri command: Deletes a saved embedding set. Called from frontend embeddings playground.
+#[tauri::command]
+pub async fn delete_embedding_set(set_id: String) -> Result<(), String> {
+    let mut index = load_sets_index()?;
+    let before = index.sets.len();
+    index.sets.retain(|s| s.id != set_id);
+    if index.sets.len() == before {
+        return Err(format!("Embedding set with id '{}' not found", set_id));
+    }
+
+    let path = get_set_file_path(&set_id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete embedding set: {}", e))?;
+    }
+    save_sets_index(&index)
+}
@@ -0,0 +1,3 @@
+pub mod embed_client;
+pub mod embed_texts;
+pub mod embedding_sets;
@@ -0,0 +1,432 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::api::chats::chat_storage::{load_chat_data, load_chats_index, ChatMessage, ChatMeta};
+use crate::api::folders::folders_storage::load_folders_index;
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// Identifier written into portable JSON exports so imports can recognise the format.
+pub const EXPORT_FORMAT_ID: &str = "oalpaca-chat-export";
+/// Version of the portable JSON export format.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Output formats supported by the export commands.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+/// A chat's metadata together with its messages, as stored in portable JSON exports.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportedChat {
+    pub meta: ChatMeta,
+    pub messages: Vec<ChatMessage>,
+}
+
+/// The root structure of the portable JSON export format. Used for single chats, folders and workspaces alike.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatExportDocument {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub chats: Vec<ExportedChat>,
+}
+
+/// Describes what is being exported; used for the document header of multi-chat exports.
+pub struct ExportScope {
+    pub kind: &'static str,
+    pub name: String,
+}
+
+/// Lookup tables for resolving workspace and folder IDs to names in rendered exports.
+struct NameLookup {
+    workspaces: HashMap<String, String>,
+    folders: HashMap<String, String>,
+}
+
+/// Returns the current UTC time as an ISO 8601 RFC3339 string. Used for the exported_at field.
+fn now_iso() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Loads workspace and folder names for rendering.
+fn load_name_lookup() -> Result<NameLookup, String> {
+    let workspaces = load_workspaces_index()?
+        .workspaces
+        .into_iter()
+        .map(|w| (w.id, w.name))
+        .collect();
+    let folders = load_folders_index()?
+        .folders
+        .into_iter()
+        .map(|f| (f.id, f.name))
+        .collect();
+    Ok(NameLookup {
+        workspaces,
+        folders,
+    })
+}
+
+/// Loads the messages of each chat and pairs them with their metadata. Used by every export command.
+pub fn load_exported_chats(chats: Vec<ChatMeta>) -> Result<Vec<ExportedChat>, String> {
+    chats
+        .into_iter()
+        .map(|meta| {
+            let data = load_chat_data(&meta.id)?;
+            Ok(ExportedChat {
+                meta,
+                messages: data.messages,
+            })
+        })
+        .collect()
+}
+
+/// Quotes a value for YAML front-matter.
+fn yaml_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// Escapes text for inclusion in HTML.
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Returns a display label for a message role.
+fn role_label(role: &str) -> String {
+    let mut chars = role.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Returns the heading for a message: its role, plus the model that wrote it for assistant replies.
+fn message_label(message: &ChatMessage) -> String {
+    match message.model {
+        Some(ref model) => format!("{} ({})", role_label(&message.role), model),
+        None => role_label(&message.role),
+    }
+}
+
+/// Returns the metadata fields shown for a chat in Markdown front-matter and HTML headers.
+fn chat_fields(meta: &ChatMeta, names: &NameLookup) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("id", meta.id.clone()),
+        ("title", meta.chat_title.clone()),
+        ("model", meta.model_used.clone()),
+    ];
+    if meta.models_used.len() > 1 {
+        fields.push(("models", meta.models_used.join(", ")));
+    }
+    fields.push((
+        "workspace",
+        names
+            .workspaces
+            .get(&meta.workspace_id)
+            .cloned()
+            .unwrap_or_else(|| meta.workspace_id.clone()),
+    ));
+    if let Some(ref folder_id) = meta.folder_id {
+        fields.push((
+            "folder",
+            names
+                .folders
+                .get(folder_id)
+                .cloned()
+                .unwrap_or_else(|| folder_id.clone()),
+        ));
+    }
+    if !meta.tags.is_empty() {
+        fields.push(("tags", meta.tags.join(", ")));
+    }
+    fields.push(("created_at", meta.created_at.clone()));
+    fields.push(("last_updated_at", meta.last_updated_at.clone()));
+    fields
+}
+
+/// Renders a chat's stored summary as a Markdown section, or nothing if the chat has not been summarized.
+fn render_markdown_summary(meta: &ChatMeta) -> String {
+    match meta.summary {
+        Some(ref summary) => format!("## Summary\n\n{}\n\n", summary.content.trim_end()),
+        None => String::new(),
+    }
+}
+
+/// Renders the messages of a chat as level-two Markdown sections, followed by their sources if any.
+fn render_markdown_messages(messages: &[ChatMessage]) -> String {
+    let mut out = String::new();
+    for message in messages {
+        out.push_str(&format!(
+            "## {}\n\n{}\n\n",
+            message_label(message),
+            message.content.trim_end()
+        ));
+        if !message.citations.is_empty() {
+            out.push_str("Sources:\n\n");
+            for citation in &message.citations {
+                out.push_str(&format!(
+                    "- {} (lines {}-{})\n",
+                    citation.source_path, citation.start_line, citation.end_line
+                ));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Renders chats as Markdown. A single chat gets its metadata as front-matter; multi-chat exports get a scope header and one section per chat.
+fn render_markdown(
+    chats: &[ExportedChat],
+    scope: Option<&ExportScope>,
+    names: &NameLookup,
+) -> String {
+    let mut out = String::from("---\n");
+
+    match (scope, chats) {
+        (None, [chat]) => {
+            for (key, value) in chat_fields(&chat.meta, names) {
+                out.push_str(&format!("{}: {}\n", key, yaml_string(&value)));
+            }
+            out.push_str(&format!(
+                "exported_at: {}\n---\n\n",
+                yaml_string(&now_iso())
+            ));
+            out.push_str(&format!("# {}\n\n", chat.meta.chat_title));
+            out.push_str(&render_markdown_summary(&chat.meta));
+            out.push_str(&render_markdown_messages(&chat.messages));
+        }
+        _ => {
+            if let Some(scope) = scope {
+                out.push_str(&format!("export: {}\n", yaml_string(scope.kind)));
+                out.push_str(&format!("name: {}\n", yaml_string(&scope.name)));
+            }
+            out.push_str(&format!("chat_count: {}\n", chats.len()));
+            out.push_str(&format!(
+                "exported_at: {}\n---\n\n",
+                yaml_string(&now_iso())
+            ));
+
+            for chat in chats {
+                out.push_str(&format!("# {}\n\n", chat.meta.chat_title));
+                for (key, value) in chat_fields(&chat.meta, names) {
+                    if key != "title" {
+                        out.push_str(&format!("- **{}**: {}\n", key, value));
+                    }
+                }
+                out.push('\n');
+                out.push_str(&render_markdown_summary(&chat.meta));
+                out.push_str(&render_markdown_messages(&chat.messages));
+            }
+        }
+    }
+
+    out
+}
+
+/// Renders chats as a standalone HTML document with inline styles.
+fn render_html(chats: &[ExportedChat], scope: Option<&ExportScope>, names: &NameLookup) -> String {
+    let title = match (scope, chats) {
+        (Some(scope), _) => format!("{} — {}", role_label(scope.kind), scope.name),
+        (None, [chat]) => chat.meta.chat_title.clone(),
+        (None, _) => "Chat export".to_string(),
+    };
+
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+         body {{ font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", sans-serif; max-width: 860px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; }}\n\
+         section.chat {{ margin-bottom: 3rem; }}\n\
+         dl {{ display: grid; grid-template-columns: max-content 1fr; gap: 0.25rem 1rem; font-size: 0.85rem; color: #57606a; }}\n\
+         dt {{ font-weight: 600; }}\n\
+         .message {{ border-radius: 8px; padding: 0.75rem 1rem; margin: 0.75rem 0; }}\n\
+         .message.user {{ background: #ddf4ff; }}\n\
+         .message.assistant {{ background: #f6f8fa; }}\n\
+         .message.system {{ background: #fff8c5; }}\n\
+         .role {{ font-weight: 600; font-size: 0.8rem; text-transform: uppercase; margin-bottom: 0.4rem; }}\n\
+         .content {{ white-space: pre-wrap; word-wrap: break-word; }}\n\
+         .summary {{ border-left: 3px solid #0969da; padding: 0.25rem 1rem; margin: 1rem 0; }}\n\
+         .sources, .exported {{ font-size: 0.8rem; color: #57606a; }}\n\
+         </style>\n</head>\n<body>\n<h1>{}</h1>\n<p class=\"exported\">Exported {}</p>\n",
+        html_escape(&title),
+        html_escape(&title),
+        html_escape(&now_iso())
+    );
+
+    for chat in chats {
+        out.push_str("<section class=\"chat\">\n");
+        if scope.is_some() || chats.len() > 1 {
+            out.push_str(&format!(
+                "<h2>{}</h2>\n",
+                html_escape(&chat.meta.chat_title)
+            ));
+        }
+        out.push_str("<dl>\n");
+        for (key, value) in chat_fields(&chat.meta, names) {
+            out.push_str(&format!(
+                "<dt>{}</dt><dd>{}</dd>\n",
+                html_escape(key),
+                html_escape(&value)
+            ));
+        }
+        out.push_str("</dl>\n");
+
+        if let Some(ref summary) = chat.meta.summary {
+            out.push_str(&format!(
+                "<div class=\"summary\">\n<div class=\"role\">Summary</div>\n<div class=\"content\">{}</div>\n</div>\n",
+                html_escape(&summary.content)
+            ));
+        }
+
+        for message in &chat.messages {
+            out.push_str(&format!(
+                "<div class=\"message {}\">\n<div class=\"role\">{}</div>\n<div class=\"content\">{}</div>\n",
+                html_escape(&message.role),
+                html_escape(&message_label(message)),
+                html_escape(&message.content)
+            ));
+            if !message.citations.is_empty() {
+                out.push_str("<ul class=\"sources\">\n");
+                for citation in &message.citations {
+                    out.push_str(&format!(
+                        "<li>{} (lines {}-{})</li>\n",
+                        html_escape(&citation.source_path),
+                        citation.start_line,
+                        citation.end_line
+                    ));
+                }
+                out.push_str("</ul>\n");
+            }
+            out.push_str("</div>\n");
+        }
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Renders chats in the requested format. `scope` is None for single-chat exports.
+pub fn render_export(
+    chats: Vec<ExportedChat>,
+    format: ExportFormat,
+    scope: Option<&ExportScope>,
+) -> Result<String, String> {
+    match format {
+        ExportFormat::Json => {
+            let document = ChatExportDocument {
+                format: EXPORT_FORMAT_ID.to_string(),
+                version: EXPORT_FORMAT_VERSION,
+                exported_at: now_iso(),
+                chats,
+            };
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| format!("Failed to serialize chat export: {}", e))
+        }
+        ExportFormat::Markdown => Ok(render_markdown(&chats, scope, &load_name_lookup()?)),
+        ExportFormat::Html => Ok(render_html(&chats, scope, &load_name_lookup()?)),
+    }
+}
+
+/// Renders chats and writes the result to the given path. Shared by all export commands.
+pub fn write_export(
+    chats: Vec<ChatMeta>,
+    format: ExportFormat,
+    scope: Option<&ExportScope>,
+    path: &str,
+) -> Result<(), String> {
+    write_exported_chats(load_exported_chats(chats)?, format, scope, path)
+}
+
+/// Renders chats whose messages are already loaded and writes the result to the given path. Used by bulk exports, which load each chat separately.
+pub fn write_exported_chats(
+    chats: Vec<ExportedChat>,
+    format: ExportFormat,
+    scope: Option<&ExportScope>,
+    path: &str,
+) -> Result<(), String> {
+    let content = render_export(chats, format, scope)?;
+    fs::write(path, content).map_err(|e| format!("Failed to write export to '{}': {}", path, e))
+}
+
+/// Tauri command: Exports a single chat as Markdown, JSON or HTML to the given path. Called from frontend chat menu.
+#[tauri::command]
+pub async fn export_chat(
+    chat_id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), String> {
+    let chat = load_chats_index()?
+        .chats
+        .into_iter()
+        .find(|c| c.id == chat_id)
+        .ok_or_else(|| format!("Chat with id '{}' not found", chat_id))?;
+
+    write_export(vec![chat], format, None, &path)
+}
+
+/// Tauri command: Exports every chat in a folder into one file at the given path. Called from frontend folder menu.
+#[tauri::command]
+pub async fn export_folder(
+    folder_id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), String> {
+    let folder = load_folders_index()?
+        .folders
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+
+    let chats: Vec<ChatMeta> = load_chats_index()?
+        .chats
+        .into_iter()
+        .filter(|c| c.folder_id.as_deref() == Some(folder_id.as_str()))
+        .collect();
+
+    let scope = ExportScope {
+        kind: "folder",
+        name: folder.name,
+    };
+    write_export(chats, format, Some(&scope), &path)
+}
+
+/// Tauri command: Exports every chat in a workspace into one file at the given path. Called from frontend workspace settings.
+#[tauri::command]
+pub async fn export_workspace(
+    workspace_id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), String> {
+    let workspace = load_workspaces_index()?
+        .workspaces
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| format!("Workspace with id '{}' not found", workspace_id))?;
+
+    let chats: Vec<ChatMeta> = load_chats_index()?
+        .chats
+        .into_iter()
+        .filter(|c| c.workspace_id == workspace_id)
+        .collect();
+
+    let scope = ExportScope {
+        kind: "workspace",
+        name: workspace.name,
+    };
+    write_export(chats, format, Some(&scope), &path)
+}
@@ -2,18 +2,43 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-/// Metadata for a folder containing id, name, workspace_id, list of chat IDs, tags, and timestamps.
+use crate::api::chats::chat_storage::{load_chats_index, save_chats_index};
+use crate::api::tags::tag_storage::{has_all_tags, insert_tag, normalize_tag};
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// Metadata for a folder containing id, name, workspace_id, parent folder (None for top-level folders), list of chat IDs, tags, and timestamps.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FolderMeta {
     pub id: String,
     pub name: String,
     pub workspace_id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub chat_ids: Vec<String>,
     pub tags: Vec<String>,
     pub created_at: String,
     pub last_updated_at: String,
 }
 
+/// A folder with its subfolders, as returned by get_folders_for_workspace.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FolderNode {
+    #[serde(flatten)]
+    pub folder: FolderMeta,
+    pub children: Vec<FolderNode>,
+}
+
+/// What happens to the chats of a deleted folder and its subfolders.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderChatPolicy {
+    /// Leave the chats unfiled.
+    #[default]
+    Release,
+    /// Move the chats into the deleted folder's parent, or leave them unfiled if it was a top-level folder.
+    MoveToParent,
+}
+
 /// The root structure for the folders index file (folders.json). Contains list of all FolderMeta entries.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FoldersIndex {
@@ -62,33 +87,154 @@ pub fn save_folders_index(index: &FoldersIndex) -> Result<(), String> {
     fs::write(&index_path, content).map_err(|e| format!("Failed to write folders index: {}", e))
 }
 
-/// Deletes all folders belonging to a workspace and removes them from the index. Called when a workspace is deleted.
-pub fn delete_folders_for_workspace(workspace_id: &str) -> Result<(), String> {
+/// Returns the IDs of a folder and all of its subfolders in the same workspace, the folder itself first.
+pub fn folder_subtree(folders: &[FolderMeta], folder_id: &str) -> Vec<String> {
+    let workspace_id = folders
+        .iter()
+        .find(|f| f.id == folder_id)
+        .map(|f| f.workspace_id.clone());
+    let mut subtree = vec![folder_id.to_string()];
+    let mut i = 0;
+    while i < subtree.len() {
+        let parent = subtree[i].clone();
+        for child in folders.iter().filter(|f| {
+            f.parent_id.as_ref() == Some(&parent) && Some(&f.workspace_id) == workspace_id.as_ref()
+        }) {
+            // Guards against a parent loop in a hand-edited index
+            if !subtree.contains(&child.id) {
+                subtree.push(child.id.clone());
+            }
+        }
+        i += 1;
+    }
+    subtree
+}
+
+/// Builds the subtree below `parent_id`, sorted by name. With a tag filter, a folder is kept if it or any subfolder carries every tag.
+fn build_folder_tree(
+    folders: &[FolderMeta],
+    parent_id: Option<&str>,
+    tags: Option<&[String]>,
+    visited: &mut Vec<String>,
+) -> Vec<FolderNode> {
+    let mut nodes = vec![];
+    for folder in folders
+        .iter()
+        .filter(|f| f.parent_id.as_deref() == parent_id)
+    {
+        if visited.contains(&folder.id) {
+            continue;
+        }
+        visited.push(folder.id.clone());
+        let children = build_folder_tree(folders, Some(&folder.id), tags, visited);
+        if children.is_empty() && !has_all_tags(&folder.tags, tags) {
+            continue;
+        }
+        nodes.push(FolderNode {
+            folder: folder.clone(),
+            children,
+        });
+    }
+    nodes.sort_by_key(|n| n.folder.name.to_lowercase());
+    nodes
+}
+
+/// Checks that a workspace exists and, when given, that a folder belongs to it. Used when moving or copying chats and folders between workspaces.
+pub fn check_move_target(workspace_id: &str, folder_id: Option<&str>) -> Result<(), String> {
+    if !load_workspaces_index()?
+        .workspaces
+        .iter()
+        .any(|w| w.id == workspace_id)
+    {
+        return Err(format!("Workspace with id '{}' not found", workspace_id));
+    }
+    if let Some(fid) = folder_id {
+        let folder = load_folders_index()?
+            .folders
+            .into_iter()
+            .find(|f| f.id == fid)
+            .ok_or_else(|| format!("Folder with id '{}' not found", fid))?;
+        if folder.workspace_id != workspace_id {
+            return Err("The target folder belongs to a different workspace".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Removes a chat from one folder's chat list and adds it to another's. Either side may be None. Used when a chat changes folder together with its workspace.
+pub fn transfer_chat_between_folders(
+    chat_id: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(), String> {
     let mut index = load_folders_index()?;
-    index.folders.retain(|f| f.workspace_id != workspace_id);
+    let now = now_iso();
+    for folder in &mut index.folders {
+        if from == Some(folder.id.as_str()) && to != from {
+            folder.chat_ids.retain(|id| id != chat_id);
+            folder.last_updated_at = now.clone();
+        }
+        if to == Some(folder.id.as_str()) && !folder.chat_ids.iter().any(|id| id == chat_id) {
+            folder.chat_ids.push(chat_id.to_string());
+            folder.last_updated_at = now.clone();
+        }
+    }
     save_folders_index(&index)
 }
 
-/// Tauri command: Returns all folders for a specific workspace. Called from frontend to display folders in sidebar.
+/// Tauri command: Returns the folder tree of a workspace, top-level folders first. With `tags`, only folders carrying every tag and their ancestors are included. Called from frontend to display folders in sidebar.
 #[tauri::command]
-pub async fn get_folders_for_workspace(workspace_id: String) -> Result<Vec<FolderMeta>, String> {
+pub async fn get_folders_for_workspace(
+    workspace_id: String,
+    tags: Option<Vec<String>>,
+) -> Result<Vec<FolderNode>, String> {
     let index = load_folders_index()?;
-    let filtered: Vec<FolderMeta> = index
+    let mut folders: Vec<FolderMeta> = index
         .folders
         .into_iter()
         .filter(|f| f.workspace_id == workspace_id)
         .collect();
-    Ok(filtered)
+
+    // A folder whose parent is gone is shown at the top level
+    let ids: Vec<String> = folders.iter().map(|f| f.id.clone()).collect();
+    for folder in &mut folders {
+        if folder.parent_id.as_ref().is_some_and(|p| !ids.contains(p)) {
+            folder.parent_id = None;
+        }
+    }
+
+    Ok(build_folder_tree(
+        &folders,
+        None,
+        tags.as_deref(),
+        &mut vec![],
+    ))
 }
 
-/// Tauri command: Creates a new folder with the given name in a workspace. Called from frontend when user creates a new folder.
+/// Tauri command: Creates a new folder with the given name in a workspace, inside `parent_id` when given. Called from frontend when user creates a new folder.
 #[tauri::command]
-pub async fn create_folder(workspace_id: String, name: String) -> Result<FolderMeta, String> {
+pub async fn create_folder(
+    workspace_id: String,
+    name: String,
+    parent_id: Option<String>,
+) -> Result<FolderMeta, String> {
     let trimmed = name.trim();
     if trimmed.is_empty() {
         return Err("Folder name cannot be empty".to_string());
     }
 
+    let mut index = load_folders_index()?;
+    if let Some(ref pid) = parent_id {
+        let parent = index
+            .folders
+            .iter()
+            .find(|f| &f.id == pid)
+            .ok_or_else(|| format!("Folder with id '{}' not found", pid))?;
+        if parent.workspace_id != workspace_id {
+            return Err("The parent folder belongs to a different workspace".to_string());
+        }
+    }
+
     let id = uuid::Uuid::new_v4().to_string();
     let now = now_iso();
 
@@ -96,13 +242,13 @@ pub async fn create_folder(workspace_id: String, name: String) -> Result<FolderM
         id: id.clone(),
         name: trimmed.to_string(),
         workspace_id,
+        parent_id,
         chat_ids: vec![],
         tags: vec![],
         created_at: now.clone(),
         last_updated_at: now,
     };
 
-    let mut index = load_folders_index()?;
     index.folders.push(folder.clone());
     save_folders_index(&index)?;
 
@@ -132,29 +278,189 @@ pub async fn rename_folder(folder_id: String, new_name: String) -> Result<(), St
     save_folders_index(&index)
 }
 
-/// Tauri command: Deletes a folder and releases all its chats (sets their folder_id to None). Called from frontend when user deletes a folder.
+/// Tauri command: Adds a tag to a folder and returns the folder's tags. Called from frontend folder menu.
+#[tauri::command]
+pub async fn add_folder_tag(folder_id: String, tag: String) -> Result<Vec<String>, String> {
+    let tag = normalize_tag(&tag)?;
+    let mut index = load_folders_index()?;
+    let folder = index
+        .folders
+        .iter_mut()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+
+    let added = insert_tag(&mut folder.tags, tag);
+    let tags = folder.tags.clone();
+    if added {
+        folder.last_updated_at = now_iso();
+        save_folders_index(&index)?;
+    }
+    Ok(tags)
+}
+
+/// Tauri command: Removes a tag from a folder and returns the folder's tags. Removing a tag the folder doesn't have is not an error. Called from frontend folder menu.
+#[tauri::command]
+pub async fn remove_folder_tag(folder_id: String, tag: String) -> Result<Vec<String>, String> {
+    let tag = normalize_tag(&tag)?;
+    let mut index = load_folders_index()?;
+    let folder = index
+        .folders
+        .iter_mut()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+
+    let before = folder.tags.len();
+    folder.tags.retain(|t| t != &tag);
+    let tags = folder.tags.clone();
+    if tags.len() != before {
+        folder.last_updated_at = now_iso();
+        save_folders_index(&index)?;
+    }
+    Ok(tags)
+}
+
+/// Tauri command: Moves a folder to a new parent, or to the top level when `parent_id` is None. Refuses to move a folder into itself or one of its subfolders. Called from frontend when dragging a folder.
+#[tauri::command]
+pub async fn move_folder(folder_id: String, parent_id: Option<String>) -> Result<(), String> {
+    let mut index = load_folders_index()?;
+
+    let workspace_id = index
+        .folders
+        .iter()
+        .find(|f| f.id == folder_id)
+        .map(|f| f.workspace_id.clone())
+        .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+
+    if let Some(ref pid) = parent_id {
+        let parent = index
+            .folders
+            .iter()
+            .find(|f| &f.id == pid)
+            .ok_or_else(|| format!("Folder with id '{}' not found", pid))?;
+        if parent.workspace_id != workspace_id {
+            return Err("The target folder belongs to a different workspace".to_string());
+        }
+        if folder_subtree(&index.folders, &folder_id).contains(pid) {
+            return Err("Cannot move a folder into itself or one of its subfolders".to_string());
+        }
+    }
+
+    let folder = index
+        .folders
+        .iter_mut()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+    folder.parent_id = parent_id;
+    folder.last_updated_at = now_iso();
+
+    save_folders_index(&index)
+}
+
+/// Tauri command: Moves a folder with its subfolders and their chats to another workspace. The folder becomes a top-level folder there. Called from frontend folder menu.
 #[tauri::command]
-pub async fn delete_folder(folder_id: String) -> Result<(), String> {
+pub async fn move_folder_to_workspace(
+    folder_id: String,
+    workspace_id: String,
+) -> Result<(), String> {
+    check_move_target(&workspace_id, None)?;
     let mut index = load_folders_index()?;
 
-    let position = index
+    let folder = index
         .folders
         .iter()
-        .position(|f| f.id == folder_id)
+        .find(|f| f.id == folder_id)
         .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+    if folder.workspace_id == workspace_id {
+        return Err("The folder is already in this workspace".to_string());
+    }
 
-    let folder = &index.folders[position];
+    let subtree = folder_subtree(&index.folders, &folder_id);
+    let now = now_iso();
+    for folder in index.folders.iter_mut().filter(|f| subtree.contains(&f.id)) {
+        folder.workspace_id = workspace_id.clone();
+        if folder.id == folder_id {
+            folder.parent_id = None;
+        }
+        folder.last_updated_at = now.clone();
+    }
+
+    let mut chats_index = load_chats_index()?;
+    for chat in chats_index.chats.iter_mut().filter(|c| {
+        c.folder_id
+            .as_ref()
+            .is_some_and(|fid| subtree.contains(fid))
+    }) {
+        chat.workspace_id = workspace_id.clone();
+        chat.last_updated_at = now.clone();
+    }
 
-    // Move chats out of the folder (set their folder_id to None)
-    let chat_ids_to_release: Vec<String> = folder.chat_ids.clone();
-    index.folders.remove(position);
     save_folders_index(&index)?;
+    save_chats_index(&chats_index)
+}
 
-    // Update each chat to remove the folder_id association
-    for chat_id in &chat_ids_to_release {
-        let _ = crate::api::chats::chat_storage::remove_chat_from_folder(chat_id);
+/// Tauri command: Moves a folder and all of its subfolders to the trash. Their chats are released, or moved to the folder's parent with `chat_policy` move_to_parent.
+/// The trash item remembers the chats so restoring puts released chats back. Called from frontend when user deletes a folder.
+#[tauri::command]
+pub async fn delete_folder(
+    folder_id: String,
+    chat_policy: Option<FolderChatPolicy>,
+) -> Result<(), String> {
+    let mut index = load_folders_index()?;
+
+    let folder = index
+        .folders
+        .iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder with id '{}' not found", folder_id))?;
+    let name = folder.name.clone();
+    let target = match chat_policy.unwrap_or_default() {
+        FolderChatPolicy::Release => None,
+        FolderChatPolicy::MoveToParent => folder.parent_id.clone(),
+    };
+
+    let subtree = folder_subtree(&index.folders, &folder_id);
+    let (removed, kept): (Vec<FolderMeta>, Vec<FolderMeta>) = index
+        .folders
+        .into_iter()
+        .partition(|f| subtree.contains(&f.id));
+    index.folders = kept;
+
+    // Release the chats, or hand them to the parent folder
+    let chat_ids: Vec<String> = removed
+        .iter()
+        .flat_map(|f| f.chat_ids.iter().cloned())
+        .collect();
+    let now = now_iso();
+    let mut chats_index = load_chats_index()?;
+    for chat in chats_index
+        .chats
+        .iter_mut()
+        .filter(|c| chat_ids.contains(&c.id))
+    {
+        chat.folder_id = target.clone();
+        chat.last_updated_at = now.clone();
+    }
+    if let Some(ref target_id) = target {
+        if let Some(parent) = index.folders.iter_mut().find(|f| &f.id == target_id) {
+            for chat_id in &chat_ids {
+                if !parent.chat_ids.contains(chat_id) {
+                    parent.chat_ids.push(chat_id.clone());
+                }
+            }
+            parent.last_updated_at = now;
+        }
     }
 
+    save_folders_index(&index)?;
+    save_chats_index(&chats_index)?;
+
+    crate::api::trash::trash_storage::add_trash_item(
+        crate::api::trash::trash_storage::TrashItemKind::Folder,
+        name,
+        None,
+        removed,
+        vec![],
+    )?;
     Ok(())
 }
 
@@ -0,0 +1,451 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+
+use crate::api::chats::chat_storage::{
+    insert_chats, now_iso, ChatData, ChatMessage, ChatMeta, ChatSummary,
+};
+use crate::api::export::chat_export::{ChatExportDocument, EXPORT_FORMAT_ID};
+use crate::api::folders::folders_storage::{load_folders_index, save_folders_index};
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// Model name recorded on imported chats whose source doesn't say which model was used.
+const UNKNOWN_MODEL: &str = "imported";
+
+/// The export formats that import_chats understands.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    ChatGpt,
+    OpenWebUi,
+    Oalpaca,
+}
+
+/// A chat that was created by an import.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportedChat {
+    pub chat_id: String,
+    pub title: String,
+    pub message_count: usize,
+}
+
+/// A conversation or part of a conversation that was not imported, and why.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkippedImport {
+    pub source: String,
+    pub reason: String,
+}
+
+/// Result of an import run: the detected format, what was created, and what was skipped.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub imported: Vec<ImportedChat>,
+    pub skipped: Vec<SkippedImport>,
+}
+
+/// A conversation parsed from any supported format, before it is turned into ChatMeta/ChatData.
+struct ParsedConversation {
+    label: String,
+    title: String,
+    model: Option<String>,
+    models_used: Vec<String>,
+    tags: Vec<String>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    messages: Vec<ChatMessage>,
+    summary: Option<ChatSummary>,
+    skipped_roles: Vec<String>,
+}
+
+/// Converts a Unix timestamp in seconds (possibly fractional) into an RFC3339 string.
+fn timestamp_to_iso(value: &Value) -> Option<String> {
+    let secs = value.as_f64()?;
+    // Open WebUI stores some timestamps in milliseconds
+    let secs = if secs > 1e11 { secs / 1000.0 } else { secs };
+    chrono::DateTime::from_timestamp(secs.trunc() as i64, (secs.fract() * 1e9) as u32)
+        .map(|dt| dt.to_rfc3339())
+}
+
+/// Maps a source role onto one of ours, or None for roles we don't store (tool, function, ...).
+fn map_role(role: &str) -> Option<&'static str> {
+    match role {
+        "user" | "human" => Some("user"),
+        "assistant" | "model" | "ai" => Some("assistant"),
+        "system" => Some("system"),
+        _ => None,
+    }
+}
+
+/// Builds a ChatMessage from a source role and content, recording unsupported roles instead. `model` is kept on assistant replies only.
+fn push_message(
+    conversation: &mut ParsedConversation,
+    role: &str,
+    content: String,
+    created_at: Option<String>,
+    model: Option<String>,
+) {
+    match map_role(role) {
+        Some(mapped) => {
+            if content.trim().is_empty() {
+                return;
+            }
+            conversation.messages.push(ChatMessage {
+                role: mapped.to_string(),
+                content,
+                created_at,
+                model: model.filter(|_| mapped == "assistant"),
+                ..Default::default()
+            });
+        }
+        None => conversation.skipped_roles.push(role.to_string()),
+    }
+}
+
+/// Parses ChatGPT's conversations.json. Each conversation is a tree in `mapping`; the active branch is followed from `current_node` back to the root.
+fn parse_chatgpt(conversations: &[Value]) -> (Vec<ParsedConversation>, Vec<SkippedImport>) {
+    let mut parsed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (i, conv) in conversations.iter().enumerate() {
+        let title = conv["title"]
+            .as_str()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or("Untitled conversation")
+            .to_string();
+        let label = format!("#{} {}", i + 1, title);
+
+        let Some(mapping) = conv["mapping"].as_object() else {
+            skipped.push(SkippedImport {
+                source: label,
+                reason: "Conversation has no message mapping".to_string(),
+            });
+            continue;
+        };
+
+        // Walk the active branch from the leaf back to the root, then reverse it
+        let mut node_ids = Vec::new();
+        let mut current = conv["current_node"].as_str().map(String::from);
+        while let Some(id) = current {
+            if node_ids.contains(&id) || node_ids.len() > mapping.len() {
+                break;
+            }
+            current = mapping
+                .get(&id)
+                .and_then(|n| n["parent"].as_str())
+                .map(String::from);
+            node_ids.push(id);
+        }
+        node_ids.reverse();
+
+        let mut conversation = ParsedConversation {
+            label: label.clone(),
+            title,
+            model: conv["default_model_slug"].as_str().map(String::from),
+            models_used: vec![],
+            tags: vec![],
+            created_at: timestamp_to_iso(&conv["create_time"]),
+            updated_at: timestamp_to_iso(&conv["update_time"]),
+            messages: vec![],
+            summary: None,
+            skipped_roles: vec![],
+        };
+
+        for id in &node_ids {
+            let Some(message) = mapping.get(id).map(|n| &n["message"]) else {
+                continue;
+            };
+            if message.is_null() {
+                continue;
+            }
+            if message["metadata"]["is_visually_hidden_from_conversation"].as_bool() == Some(true) {
+                continue;
+            }
+            let role = message["author"]["role"].as_str().unwrap_or_default();
+            let content = match message["content"]["parts"].as_array() {
+                Some(parts) => parts
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => message["content"]["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            let model = message["metadata"]["model_slug"].as_str().map(String::from);
+            if conversation.model.is_none() {
+                conversation.model = model.clone();
+            }
+            push_message(
+                &mut conversation,
+                role,
+                content,
+                timestamp_to_iso(&message["create_time"]),
+                model,
+            );
+        }
+
+        parsed.push(conversation);
+    }
+
+    (parsed, skipped)
+}
+
+/// Parses an Open WebUI export (a list of chats, or a single chat). Uses the `history` tree from `currentId` when present, else the flat `messages` list.
+fn parse_open_webui(items: &[Value]) -> (Vec<ParsedConversation>, Vec<SkippedImport>) {
+    let mut parsed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        let chat = &item["chat"];
+        let title = item["title"]
+            .as_str()
+            .or_else(|| chat["title"].as_str())
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or("Untitled conversation")
+            .to_string();
+        let label = format!("#{} {}", i + 1, title);
+
+        if !chat.is_object() {
+            skipped.push(SkippedImport {
+                source: label,
+                reason: "Entry has no chat object".to_string(),
+            });
+            continue;
+        }
+
+        let mut source_messages: Vec<&Value> = Vec::new();
+        if let (Some(history), Some(current_id)) = (
+            chat["history"]["messages"].as_object(),
+            chat["history"]["currentId"].as_str(),
+        ) {
+            let mut current = Some(current_id.to_string());
+            while let Some(id) = current {
+                let Some(node) = history.get(&id) else {
+                    break;
+                };
+                if source_messages.len() > history.len() {
+                    break;
+                }
+                source_messages.push(node);
+                current = node["parentId"].as_str().map(String::from);
+            }
+            source_messages.reverse();
+        } else if let Some(messages) = chat["messages"].as_array() {
+            source_messages = messages.iter().collect();
+        }
+
+        let mut conversation = ParsedConversation {
+            label: label.clone(),
+            title,
+            model: chat["models"][0].as_str().map(String::from),
+            models_used: vec![],
+            tags: vec![],
+            created_at: timestamp_to_iso(&item["created_at"]),
+            updated_at: timestamp_to_iso(&item["updated_at"]),
+            messages: vec![],
+            summary: None,
+            skipped_roles: vec![],
+        };
+
+        for message in source_messages {
+            let model = message["model"].as_str().map(String::from);
+            if conversation.model.is_none() {
+                conversation.model = model.clone();
+            }
+            push_message(
+                &mut conversation,
+                message["role"].as_str().unwrap_or_default(),
+                message["content"].as_str().unwrap_or_default().to_string(),
+                timestamp_to_iso(&message["timestamp"]),
+                model,
+            );
+        }
+
+        parsed.push(conversation);
+    }
+
+    (parsed, skipped)
+}
+
+/// Parses our own portable JSON export. Messages are kept as-is.
+fn parse_oalpaca(document: ChatExportDocument) -> Vec<ParsedConversation> {
+    document
+        .chats
+        .into_iter()
+        .enumerate()
+        .map(|(i, chat)| ParsedConversation {
+            label: format!("#{} {}", i + 1, chat.meta.chat_title),
+            title: chat.meta.chat_title,
+            model: Some(chat.meta.model_used),
+            models_used: chat.meta.models_used,
+            tags: chat.meta.tags,
+            created_at: Some(chat.meta.created_at),
+            updated_at: Some(chat.meta.last_updated_at),
+            messages: chat.messages,
+            summary: chat.meta.summary,
+            skipped_roles: vec![],
+        })
+        .collect()
+}
+
+/// Detects the format of a parsed export file and extracts its conversations.
+fn parse_export(
+    value: Value,
+) -> Result<(ImportSource, Vec<ParsedConversation>, Vec<SkippedImport>), String> {
+    if value["format"].as_str() == Some(EXPORT_FORMAT_ID) {
+        let document: ChatExportDocument = serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse chat export: {}", e))?;
+        return Ok((ImportSource::Oalpaca, parse_oalpaca(document), vec![]));
+    }
+
+    let items: Vec<Value> = match value {
+        Value::Array(items) => items,
+        Value::Object(_) => vec![value],
+        _ => return Err("Unrecognised export file: expected a JSON array or object".to_string()),
+    };
+
+    if items.iter().any(|i| i["mapping"].is_object()) {
+        let (parsed, skipped) = parse_chatgpt(&items);
+        return Ok((ImportSource::ChatGpt, parsed, skipped));
+    }
+    if items.iter().any(|i| i["chat"].is_object()) {
+        let (parsed, skipped) = parse_open_webui(&items);
+        return Ok((ImportSource::OpenWebUi, parsed, skipped));
+    }
+
+    Err(
+        "Unrecognised export file: expected ChatGPT conversations.json, an Open WebUI export, or an Oalpaca export"
+            .to_string(),
+    )
+}
+
+/// Tauri command: Imports conversations from a ChatGPT, Open WebUI or Oalpaca export file into a workspace and optional folder. Returns what was imported and what was skipped.
+#[tauri::command]
+pub async fn import_chats(
+    path: String,
+    workspace_id: String,
+    folder_id: Option<String>,
+) -> Result<ImportReport, String> {
+    let workspaces = load_workspaces_index()?;
+    if !workspaces.workspaces.iter().any(|w| w.id == workspace_id) {
+        return Err(format!("Workspace with id '{}' not found", workspace_id));
+    }
+
+    let mut folders_index = load_folders_index()?;
+    if let Some(ref fid) = folder_id {
+        let folder = folders_index
+            .folders
+            .iter()
+            .find(|f| &f.id == fid)
+            .ok_or_else(|| format!("Folder with id '{}' not found", fid))?;
+        if folder.workspace_id != workspace_id {
+            return Err("The target folder belongs to a different workspace".to_string());
+        }
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read import file '{}': {}", path, e))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse import file as JSON: {}", e))?;
+
+    let (source, conversations, mut skipped) = parse_export(value)?;
+
+    let now = now_iso();
+    let mut imported = Vec::new();
+    let mut new_chats = Vec::new();
+
+    for conversation in conversations {
+        if !conversation.skipped_roles.is_empty() {
+            let mut roles = conversation.skipped_roles.clone();
+            roles.sort();
+            roles.dedup();
+            skipped.push(SkippedImport {
+                source: conversation.label.clone(),
+                reason: format!(
+                    "Skipped {} message(s) with unsupported roles: {}",
+                    conversation.skipped_roles.len(),
+                    roles.join(", ")
+                ),
+            });
+        }
+        if conversation.messages.is_empty() {
+            skipped.push(SkippedImport {
+                source: conversation.label,
+                reason: "Conversation has no importable messages".to_string(),
+            });
+            continue;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let created_at = conversation.created_at.unwrap_or_else(|| now.clone());
+        let last_updated_at = conversation
+            .updated_at
+            .unwrap_or_else(|| created_at.clone());
+
+        // Models in order of first reply, after any list the source already recorded
+        let mut models_used = conversation.models_used;
+        for model in conversation
+            .messages
+            .iter()
+            .filter_map(|m| m.model.as_ref())
+        {
+            if !models_used.contains(model) {
+                models_used.push(model.clone());
+            }
+        }
+
+        imported.push(ImportedChat {
+            chat_id: id.clone(),
+            title: conversation.title.clone(),
+            message_count: conversation.messages.len(),
+        });
+
+        new_chats.push((
+            ChatMeta {
+                id: id.clone(),
+                chat_title: conversation.title,
+                file_location: format!(".data/chats/{}.json", id),
+                model_used: conversation
+                    .model
+                    .unwrap_or_else(|| UNKNOWN_MODEL.to_string()),
+                models_used,
+                workspace_id: workspace_id.clone(),
+                folder_id: folder_id.clone(),
+                created_at,
+                last_updated_at,
+                rag_enabled: false,
+                custom_title: true,
+                summary: conversation.summary,
+                preset_id: None,
+                tags: conversation.tags,
+                pinned: false,
+                starred: false,
+                archived: false,
+            },
+            ChatData {
+                messages: conversation.messages,
+                ..Default::default()
+            },
+        ));
+    }
+
+    insert_chats(new_chats)?;
+
+    if let Some(ref fid) = folder_id {
+        if let Some(folder) = folders_index.folders.iter_mut().find(|f| &f.id == fid) {
+            folder
+                .chat_ids
+                .extend(imported.iter().map(|c| c.chat_id.clone()));
+            folder.last_updated_at = now;
+        }
+        save_folders_index(&folders_index)?;
+    }
+
+    Ok(ImportReport {
+        source,
+        imported,
+        skipped,
+    })
+}
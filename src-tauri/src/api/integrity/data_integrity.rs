@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+use crate::api::chats::chat_storage::{
+    get_chat_file_path, list_chat_file_ids, load_chat_data, load_chats_index, now_iso,
+    remove_from_search_indexes, save_chats_index, ChatData, ChatMeta,
+};
+use crate::api::folders::folders_storage::{load_folders_index, save_folders_index};
+use crate::api::trash::trash_storage::{add_chat_trash_items, stash_chat_files, undo_trash};
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// Model name recorded on chats recovered from files the index didn't know about.
+const UNKNOWN_MODEL: &str = "unknown";
+/// Chat files written this recently are not treated as orphaned. New chats and imports write the file before adding the index entry.
+const ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// The kinds of inconsistency verify_data_integrity looks for.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssueKind {
+    /// A folder lists a chat ID that is not in the chats index. Repair drops the ID.
+    DanglingChatId,
+    /// A folder's chat list and a chat's folder_id disagree. Repair follows the chat's folder_id.
+    FolderMismatch,
+    /// A chat or folder points at a folder that no longer exists. Repair moves it to the top level.
+    MissingFolder,
+    /// A chat or folder points at a workspace that no longer exists. Repair moves it to the active workspace.
+    MissingWorkspace,
+    /// A chat file in .data/chats has no index entry. Repair moves it to the trash as a recovered chat.
+    OrphanedChatFile,
+    /// An index entry has no chat file. Repair moves the entry to the trash.
+    MissingChatFile,
+}
+
+/// A single inconsistency, naming the chat or folder it concerns.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    pub subject_id: String,
+    pub detail: String,
+}
+
+/// Result of an integrity check. `repaired` is true when the issues were fixed rather than only reported.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+    pub repaired: bool,
+    pub checked_at: String,
+}
+
+/// Builds a chat entry for a data file the index has lost, titled from its first user message.
+fn recovered_chat_meta(chat_id: &str, data: &ChatData, workspace_id: &str) -> ChatMeta {
+    let first_message = data
+        .messages
+        .iter()
+        .find(|m| m.role == "user")
+        .map(|m| m.content.trim().to_string())
+        .filter(|c| !c.is_empty());
+    let chat_title = match first_message {
+        Some(text) if text.chars().count() > 50 => {
+            format!("{}...", text.chars().take(50).collect::<String>())
+        }
+        Some(text) => text,
+        None => "Recovered chat".to_string(),
+    };
+    let model = data
+        .messages
+        .iter()
+        .rev()
+        .find_map(|m| m.model.clone())
+        .unwrap_or_else(|| UNKNOWN_MODEL.to_string());
+    let now = now_iso();
+    let created_at = data
+        .messages
+        .first()
+        .and_then(|m| m.created_at.clone())
+        .unwrap_or_else(|| now.clone());
+
+    ChatMeta {
+        id: chat_id.to_string(),
+        chat_title,
+        file_location: format!(".data/chats/{}.json", chat_id),
+        model_used: model,
+        models_used: vec![],
+        workspace_id: workspace_id.to_string(),
+        folder_id: None,
+        created_at,
+        last_updated_at: now,
+        rag_enabled: false,
+        custom_title: false,
+        summary: None,
+        preset_id: None,
+        tags: vec![],
+        pinned: false,
+        starred: false,
+        archived: false,
+    }
+}
+
+/// Returns true if a chat file was modified within the grace period, so it may belong to a chat whose index entry is still being written.
+fn recently_written(chat_id: &str) -> Result<bool, String> {
+    let modified = std::fs::metadata(get_chat_file_path(chat_id)?)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Failed to read chat file '{}': {}", chat_id, e))?;
+    // A modification time in the future also counts as recent
+    Ok(SystemTime::now()
+        .duration_since(modified)
+        .map_or(true, |age| age < ORPHAN_GRACE_PERIOD))
+}
+
+/// Checks the cross-references between workspaces, folders, chats and chat files, and fixes them when `repair` is true.
+/// Orphaned files and entries without files go to the trash rather than being deleted, so every repair can be undone.
+pub fn check_data_integrity(repair: bool) -> Result<IntegrityReport, String> {
+    let workspaces = load_workspaces_index()?;
+    let mut folders_index = load_folders_index()?;
+    let mut chats_index = load_chats_index()?;
+    let mut issues = vec![];
+
+    let workspace_exists = |id: &str| workspaces.workspaces.iter().any(|w| w.id == id);
+    let active_workspace = workspaces.active_workspace_id.clone();
+
+    // Folders in deleted workspaces, and subfolders of deleted folders
+    let folder_ids: Vec<String> = folders_index.folders.iter().map(|f| f.id.clone()).collect();
+    for folder in &mut folders_index.folders {
+        if !workspace_exists(&folder.workspace_id) {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingWorkspace,
+                subject_id: folder.id.clone(),
+                detail: format!(
+                    "Folder '{}' belongs to missing workspace '{}'",
+                    folder.name, folder.workspace_id
+                ),
+            });
+            folder.workspace_id = active_workspace.clone();
+            folder.parent_id = None;
+        }
+        if let Some(parent_id) = folder.parent_id.clone() {
+            if !folder_ids.contains(&parent_id) {
+                issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::MissingFolder,
+                    subject_id: folder.id.clone(),
+                    detail: format!(
+                        "Folder '{}' is inside missing folder '{}'",
+                        folder.name, parent_id
+                    ),
+                });
+                folder.parent_id = None;
+            }
+        }
+    }
+
+    // Chats in deleted workspaces or folders, and entries whose file is gone
+    let mut missing_files: Vec<ChatMeta> = vec![];
+    let mut kept_chats: Vec<ChatMeta> = vec![];
+    for mut chat in std::mem::take(&mut chats_index.chats) {
+        if !get_chat_file_path(&chat.id)?.exists() {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingChatFile,
+                subject_id: chat.id.clone(),
+                detail: format!("Chat '{}' has no data file", chat.chat_title),
+            });
+            missing_files.push(chat);
+            continue;
+        }
+        if !workspace_exists(&chat.workspace_id) {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingWorkspace,
+                subject_id: chat.id.clone(),
+                detail: format!(
+                    "Chat '{}' belongs to missing workspace '{}'",
+                    chat.chat_title, chat.workspace_id
+                ),
+            });
+            chat.workspace_id = active_workspace.clone();
+            chat.folder_id = None;
+        }
+        if let Some(folder_id) = chat.folder_id.clone() {
+            match folders_index.folders.iter().find(|f| f.id == folder_id) {
+                None => {
+                    issues.push(IntegrityIssue {
+                        kind: IntegrityIssueKind::MissingFolder,
+                        subject_id: chat.id.clone(),
+                        detail: format!(
+                            "Chat '{}' is in missing folder '{}'",
+                            chat.chat_title, folder_id
+                        ),
+                    });
+                    chat.folder_id = None;
+                }
+                Some(folder) if folder.workspace_id != chat.workspace_id => {
+                    issues.push(IntegrityIssue {
+                        kind: IntegrityIssueKind::FolderMismatch,
+                        subject_id: chat.id.clone(),
+                        detail: format!(
+                            "Chat '{}' is in folder '{}' from another workspace",
+                            chat.chat_title, folder.name
+                        ),
+                    });
+                    chat.folder_id = None;
+                }
+                Some(_) => {}
+            }
+        }
+        kept_chats.push(chat);
+    }
+    chats_index.chats = kept_chats;
+
+    // Folder chat lists must match the chats' folder_id, which is the source of truth
+    for folder in &mut folders_index.folders {
+        let mut chat_ids: Vec<String> = vec![];
+        for chat_id in std::mem::take(&mut folder.chat_ids) {
+            if chat_ids.contains(&chat_id) {
+                continue;
+            }
+            match chats_index.chats.iter().find(|c| c.id == chat_id) {
+                None => issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::DanglingChatId,
+                    subject_id: folder.id.clone(),
+                    detail: format!("Folder '{}' lists unknown chat '{}'", folder.name, chat_id),
+                }),
+                Some(chat) if chat.folder_id.as_deref() != Some(folder.id.as_str()) => {
+                    issues.push(IntegrityIssue {
+                        kind: IntegrityIssueKind::FolderMismatch,
+                        subject_id: folder.id.clone(),
+                        detail: format!(
+                            "Folder '{}' lists chat '{}', which is filed elsewhere",
+                            folder.name, chat.chat_title
+                        ),
+                    })
+                }
+                Some(_) => chat_ids.push(chat_id),
+            }
+        }
+        for chat in chats_index
+            .chats
+            .iter()
+            .filter(|c| c.folder_id.as_deref() == Some(folder.id.as_str()))
+        {
+            if !chat_ids.contains(&chat.id) {
+                issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::FolderMismatch,
+                    subject_id: chat.id.clone(),
+                    detail: format!(
+                        "Chat '{}' is missing from the list of folder '{}'",
+                        chat.chat_title, folder.name
+                    ),
+                });
+                chat_ids.push(chat.id.clone());
+            }
+        }
+        folder.chat_ids = chat_ids;
+    }
+
+    // Chat files the index doesn't know about. An unreadable file is still recovered, just without a title
+    let mut orphaned: Vec<(String, ChatData)> = vec![];
+    for chat_id in list_chat_file_ids()? {
+        if !chats_index.chats.iter().any(|c| c.id == chat_id) && !recently_written(&chat_id)? {
+            let (data, detail) = match load_chat_data(&chat_id) {
+                Ok(data) => (
+                    data,
+                    format!("Chat file '{}.json' is not in the chats index", chat_id),
+                ),
+                Err(e) => (
+                    ChatData::default(),
+                    format!(
+                        "Chat file '{}.json' is not in the chats index and can't be read: {}",
+                        chat_id, e
+                    ),
+                ),
+            };
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::OrphanedChatFile,
+                subject_id: chat_id.clone(),
+                detail,
+            });
+            orphaned.push((chat_id, data));
+        }
+    }
+
+    if repair && !issues.is_empty() {
+        // Everything that leaves the index goes to the trash first, so a failed repair never loses a chat
+        let orphaned_ids: Vec<String> = orphaned.iter().map(|(id, _)| id.clone()).collect();
+        stash_chat_files(&orphaned_ids)?;
+        let removed_ids: Vec<String> = missing_files.iter().map(|c| c.id.clone()).collect();
+        let mut trashed = missing_files;
+        for (chat_id, data) in &orphaned {
+            trashed.push(recovered_chat_meta(chat_id, data, &active_workspace));
+        }
+        let trash_ids = match add_chat_trash_items(trashed) {
+            Ok(ids) => ids,
+            Err(e) => {
+                undo_trash(&[], &orphaned_ids);
+                return Err(e);
+            }
+        };
+        let saved = save_folders_index(&folders_index).and_then(|_| save_chats_index(&chats_index));
+        if let Err(e) = saved {
+            undo_trash(&trash_ids, &orphaned_ids);
+            return Err(e);
+        }
+
+        remove_from_search_indexes(&removed_ids);
+    }
+
+    Ok(IntegrityReport {
+        repaired: repair && !issues.is_empty(),
+        issues,
+        checked_at: now_iso(),
+    })
+}
+
+/// Tauri command: Reports inconsistencies between workspaces, folders, chats and chat files, and repairs them when `repair` is true. Called from frontend settings.
+#[tauri::command]
+pub async fn verify_data_integrity(repair: Option<bool>) -> Result<IntegrityReport, String> {
+    check_data_integrity(repair.unwrap_or(false))
+}
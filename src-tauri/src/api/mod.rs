@@ -1,4 +1,18 @@
+pub mod backup;
 pub mod chats;
+pub mod comparisons;
+pub mod completions;
+pub mod embeddings;
+pub mod export;
 pub mod folders;
+pub mod import;
+pub mod integrity;
 pub mod models;
+pub mod presets;
+pub mod prompts;
+pub mod rag;
+pub mod search;
+pub mod settings;
+pub mod tags;
+pub mod trash;
 pub mod workspace;
@@ -0,0 +1,2 @@
+pub mod preset_storage;
+pub mod preset_transfer;
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Tools a preset can turn on for the chats started from it.
+pub const KNOWN_TOOLS: &[&str] = &["rag"];
+
+/// The editable fields of a preset, as sent by the frontend and stored in export files.
+/// `options` are Ollama generation options (temperature, top_p, ...); `keep_alive` is a duration string such as "10m" or a number of seconds.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PresetInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub model: String,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub options: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub keep_alive: Option<serde_json::Value>,
+}
+
+/// A saved chat setup (model, system prompt, generation options, tools and keep_alive) that can be applied when starting a chat.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Preset {
+    pub id: String,
+    #[serde(flatten)]
+    pub settings: PresetInput,
+    pub created_at: String,
+    pub last_updated_at: String,
+}
+
+/// The root structure for the presets index file (presets_index.json). Presets are small, so they are stored inline.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PresetsIndex {
+    pub presets: Vec<Preset>,
+}
+
+/// Returns the path to the .data directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create .data directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the presets_index.json file.
+fn get_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("presets_index.json"))
+}
+
+/// Loads the presets index, creating it with an empty list if it doesn't exist.
+pub fn load_presets_index() -> Result<PresetsIndex, String> {
+    let index_path = get_index_path()?;
+    if !index_path.exists() {
+        let index = PresetsIndex { presets: vec![] };
+        save_presets_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read presets index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse presets index: {}", e))
+}
+
+/// Saves the presets index to presets_index.json.
+pub fn save_presets_index(index: &PresetsIndex) -> Result<(), String> {
+    let index_path = get_index_path()?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize presets index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write presets index: {}", e))
+}
+
+/// Returns a single preset from the index. Used by send_chat_message when starting or continuing a chat with a preset.
+pub fn get_preset_by_id(preset_id: &str) -> Result<Preset, String> {
+    load_presets_index()?
+        .presets
+        .into_iter()
+        .find(|p| p.id == preset_id)
+        .ok_or_else(|| format!("Preset with id '{}' not found", preset_id))
+}
+
+/// Checks and normalizes preset fields: trims the name and model, drops empty text, and rejects unknown tools or malformed options.
+pub fn validate_preset(mut input: PresetInput) -> Result<PresetInput, String> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    input.model = input.model.trim().to_string();
+    if input.model.is_empty() {
+        return Err(format!("Preset '{}' has no model", input.name));
+    }
+    input.description = input.description.filter(|d| !d.trim().is_empty());
+    input.system_prompt = input.system_prompt.filter(|p| !p.trim().is_empty());
+
+    if input.options.as_ref().is_some_and(|o| !o.is_object()) {
+        return Err(format!(
+            "Options of preset '{}' must be a JSON object",
+            input.name
+        ));
+    }
+    if let Some(tool) = input
+        .tools
+        .iter()
+        .find(|t| !KNOWN_TOOLS.contains(&t.as_str()))
+    {
+        return Err(format!(
+            "Unknown tool '{}' in preset '{}'. Supported tools: {}",
+            tool,
+            input.name,
+            KNOWN_TOOLS.join(", ")
+        ));
+    }
+    input.tools.sort();
+    input.tools.dedup();
+    if input
+        .keep_alive
+        .as_ref()
+        .is_some_and(|k| !(k.is_string() || k.is_number()))
+    {
+        return Err(format!(
+            "keep_alive of preset '{}' must be a duration string or a number of seconds",
+            input.name
+        ));
+    }
+    Ok(input)
+}
+
+/// Builds a new preset with a fresh ID from validated settings.
+pub fn new_preset(settings: PresetInput) -> Preset {
+    let now = chrono::Utc::now().to_rfc3339();
+    Preset {
+        id: uuid::Uuid::new_v4().to_string(),
+        settings,
+        created_at: now.clone(),
+        last_updated_at: now,
+    }
+}
+
+/// Tauri command: Returns all presets. Called from frontend preset picker and settings.
+#[tauri::command]
+pub async fn list_presets() -> Result<Vec<Preset>, String> {
+    Ok(load_presets_index()?.presets)
+}
+
+/// Tauri command: Returns a single preset. Called from frontend preset editor.
+#[tauri::command]
+pub async fn get_preset(preset_id: String) -> Result<Preset, String> {
+    get_preset_by_id(&preset_id)
+}
+
+/// Tauri command: Saves a new preset. Called from frontend preset editor.
+#[tauri::command]
+pub async fn create_preset(preset: PresetInput) -> Result<Preset, String> {
+    let preset = new_preset(validate_preset(preset)?);
+
+    let mut index = load_presets_index()?;
+    index.presets.push(preset.clone());
+    save_presets_index(&index)?;
+
+    Ok(preset)
+}
+
+/// Tauri command: Replaces a preset's settings. Chats started from it pick up the change on their next message. Called from frontend preset editor.
+#[tauri::command]
+pub async fn update_preset(preset_id: String, preset: PresetInput) -> Result<Preset, String> {
+    let settings = validate_preset(preset)?;
+
+    let mut index = load_presets_index()?;
+    let existing = index
+        .presets
+        .iter_mut()
+        .find(|p| p.id == preset_id)
+        .ok_or_else(|| format!("Preset with id '{}' not found", preset_id))?;
+    existing.settings = settings;
+    existing.last_updated_at = chrono::Utc::now().to_rfc3339();
+    let updated = existing.clone();
+
+    save_presets_index(&index)?;
+    Ok(updated)
+}
+
+/// Tauri command: Deletes a preset. Chats started from it keep their preset_id but fall back to plain requests. Called from frontend preset settings.
+#[tauri::command]
+pub async fn delete_preset(preset_id: String) -> Result<(), String> {
+    let mut index = load_presets_index()?;
+    let before = index.presets.len();
+    index.presets.retain(|p| p.id != preset_id);
+    if index.presets.len() == before {
+        return Err(format!("Preset with id '{}' not found", preset_id));
+    }
+    save_presets_index(&index)
+}
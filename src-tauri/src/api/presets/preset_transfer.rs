@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use super::preset_storage::{
+    load_presets_index, new_preset, save_presets_index, validate_preset, Preset, PresetInput,
+};
+
+/// Format identifier written into preset export files so imports can recognise them.
+pub const PRESET_EXPORT_FORMAT_ID: &str = "oalpaca-presets";
+/// Version of the preset export format.
+pub const PRESET_EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// A shareable preset file. Only the settings are exported; IDs and timestamps are assigned on import.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PresetExportDocument {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub presets: Vec<PresetInput>,
+}
+
+/// Tauri command: Writes the given presets, or all presets when `preset_ids` is None, to a JSON file at the given path. Called from frontend preset settings.
+#[tauri::command]
+pub async fn export_presets(preset_ids: Option<Vec<String>>, path: String) -> Result<(), String> {
+    let presets = load_presets_index()?.presets;
+    let selected: Vec<PresetInput> = match preset_ids {
+        Some(ids) => {
+            let mut selected = vec![];
+            for id in &ids {
+                let preset = presets
+                    .iter()
+                    .find(|p| &p.id == id)
+                    .ok_or_else(|| format!("Preset with id '{}' not found", id))?;
+                selected.push(preset.settings.clone());
+            }
+            selected
+        }
+        None => presets.into_iter().map(|p| p.settings).collect(),
+    };
+    if selected.is_empty() {
+        return Err("There are no presets to export".to_string());
+    }
+
+    let document = PresetExportDocument {
+        format: PRESET_EXPORT_FORMAT_ID.to_string(),
+        version: PRESET_EXPORT_FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        presets: selected,
+    };
+    let content = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize preset export: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write export to '{}': {}", path, e))
+}
+
+/// Tauri command: Adds the presets from an export file as new presets. Every preset is validated before any is saved. Called from frontend preset settings.
+#[tauri::command]
+pub async fn import_presets(path: String) -> Result<Vec<Preset>, String> {
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read import file '{}': {}", path, e))?;
+    let document: PresetExportDocument = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse preset file: {}", e))?;
+    if document.format != PRESET_EXPORT_FORMAT_ID {
+        return Err(format!(
+            "Unrecognised preset file: expected format '{}'",
+            PRESET_EXPORT_FORMAT_ID
+        ));
+    }
+    if document.version > PRESET_EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "Preset file version {} is newer than supported version {}",
+            document.version, PRESET_EXPORT_FORMAT_VERSION
+        ));
+    }
+
+    let mut imported = vec![];
+    for settings in document.presets {
+        imported.push(new_preset(validate_preset(settings)?));
+    }
+
+    let mut index = load_presets_index()?;
+    index.presets.extend(imported.iter().cloned());
+    save_presets_index(&index)?;
+
+    Ok(imported)
+}
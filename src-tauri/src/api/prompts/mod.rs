@@ -0,0 +1,2 @@
+pub mod prompt_render;
+pub mod prompt_storage;
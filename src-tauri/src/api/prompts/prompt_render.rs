@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use super::prompt_storage::{get_prompt_template, PromptTemplate};
+
+/// A piece of template text: literal text, or a `{{variable}}` placeholder.
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+/// Returns true for names allowed inside `{{ }}`: letters, digits, `_`, `-` and `.`.
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Splits template text into literal text and placeholders. Braces that don't form a valid `{{name}}` are kept as text.
+fn parse_segments(content: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+        let name = after_open[..end].trim();
+        if is_variable_name(name) {
+            if start > 0 {
+                segments.push(Segment::Text(&rest[..start]));
+            }
+            segments.push(Segment::Variable(name));
+        } else {
+            segments.push(Segment::Text(&rest[..start + 2]));
+            rest = after_open;
+            continue;
+        }
+        rest = &after_open[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// Returns the variable names used in template text, in order of first appearance.
+pub fn template_variables(content: &str) -> Vec<String> {
+    let mut variables: Vec<String> = vec![];
+    for segment in parse_segments(content) {
+        if let Segment::Variable(name) = segment {
+            if !variables.iter().any(|v| v == name) {
+                variables.push(name.to_string());
+            }
+        }
+    }
+    variables
+}
+
+/// Fills a template's placeholders from `values`, falling back to the template's defaults. Fails listing every variable that has neither.
+pub fn render_template(
+    template: &PromptTemplate,
+    values: &BTreeMap<String, String>,
+) -> Result<String, String> {
+    let missing: Vec<&str> = template
+        .variables
+        .iter()
+        .filter(|v| !values.contains_key(*v) && !template.defaults.contains_key(*v))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Prompt '{}' is missing values for: {}",
+            template.name,
+            missing.join(", ")
+        ));
+    }
+
+    let mut out = String::new();
+    for segment in parse_segments(&template.content) {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Variable(name) => {
+                let value = values.get(name).or_else(|| template.defaults.get(name));
+                out.push_str(value.map(String::as_str).unwrap_or_default());
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Tauri command: Renders a prompt template with the given variable values. Called from frontend prompt library to preview a prompt before sending it.
+#[tauri::command]
+pub async fn render_prompt(
+    prompt_id: String,
+    variables: Option<BTreeMap<String, String>>,
+) -> Result<String, String> {
+    let template = get_prompt_template(&prompt_id)?;
+    render_template(&template, &variables.unwrap_or_default())
+}
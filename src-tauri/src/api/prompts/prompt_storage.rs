@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::prompt_render::template_variables;
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// A reusable prompt with `{{variable}}` placeholders. `workspace_id` is None for global templates. `variables` is derived from the content;
+/// variables listed in `defaults` are optional when rendering.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub workspace_id: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<String>,
+    #[serde(default)]
+    pub defaults: BTreeMap<String, String>,
+    pub created_at: String,
+    pub last_updated_at: String,
+}
+
+/// The root structure for the prompts index file (prompts_index.json). Templates are small, so they are stored inline.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptsIndex {
+    pub prompts: Vec<PromptTemplate>,
+}
+
+/// Returns the path to the .data directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create .data directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the prompts_index.json file.
+fn get_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("prompts_index.json"))
+}
+
+/// Loads the prompts index, creating it with an empty list if it doesn't exist.
+fn load_prompts_index() -> Result<PromptsIndex, String> {
+    let index_path = get_index_path()?;
+    if !index_path.exists() {
+        let index = PromptsIndex { prompts: vec![] };
+        save_prompts_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read prompts index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse prompts index: {}", e))
+}
+
+/// Saves the prompts index to prompts_index.json.
+fn save_prompts_index(index: &PromptsIndex) -> Result<(), String> {
+    let index_path = get_index_path()?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize prompts index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write prompts index: {}", e))
+}
+
+/// Returns a single template from the index. Used by render_prompt and send_chat_message.
+pub fn get_prompt_template(prompt_id: &str) -> Result<PromptTemplate, String> {
+    load_prompts_index()?
+        .prompts
+        .into_iter()
+        .find(|p| p.id == prompt_id)
+        .ok_or_else(|| format!("Prompt with id '{}' not found", prompt_id))
+}
+
+/// Checks a template's name and content and returns the trimmed name.
+fn validate_template(name: &str, content: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Prompt name cannot be empty".to_string());
+    }
+    if content.trim().is_empty() {
+        return Err("Prompt text cannot be empty".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Drops defaults for variables the content no longer uses, so stale values don't linger after an edit.
+fn retain_used_defaults(
+    mut defaults: BTreeMap<String, String>,
+    variables: &[String],
+) -> BTreeMap<String, String> {
+    defaults.retain(|name, _| variables.contains(name));
+    defaults
+}
+
+/// Tauri command: Returns the global templates plus, when a workspace is given, that workspace's templates. Called from frontend prompt library.
+#[tauri::command]
+pub async fn list_prompts(workspace_id: Option<String>) -> Result<Vec<PromptTemplate>, String> {
+    Ok(load_prompts_index()?
+        .prompts
+        .into_iter()
+        .filter(|p| p.workspace_id.is_none() || p.workspace_id == workspace_id)
+        .collect())
+}
+
+/// Tauri command: Returns a single template. Called from frontend when editing or filling in a prompt.
+#[tauri::command]
+pub async fn get_prompt(prompt_id: String) -> Result<PromptTemplate, String> {
+    get_prompt_template(&prompt_id)
+}
+
+/// Tauri command: Creates a template, global when `workspace_id` is None. Called from frontend prompt library.
+#[tauri::command]
+pub async fn create_prompt(
+    name: String,
+    content: String,
+    description: Option<String>,
+    workspace_id: Option<String>,
+    defaults: Option<BTreeMap<String, String>>,
+) -> Result<PromptTemplate, String> {
+    let name = validate_template(&name, &content)?;
+    if let Some(ref ws) = workspace_id {
+        if !load_workspaces_index()?
+            .workspaces
+            .iter()
+            .any(|w| &w.id == ws)
+        {
+            return Err(format!("Workspace with id '{}' not found", ws));
+        }
+    }
+
+    let variables = template_variables(&content);
+    let now = chrono::Utc::now().to_rfc3339();
+    let prompt = PromptTemplate {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        description: description.filter(|d| !d.trim().is_empty()),
+        content,
+        workspace_id,
+        defaults: retain_used_defaults(defaults.unwrap_or_default(), &variables),
+        variables,
+        created_at: now.clone(),
+        last_updated_at: now,
+    };
+
+    let mut index = load_prompts_index()?;
+    index.prompts.push(prompt.clone());
+    save_prompts_index(&index)?;
+
+    Ok(prompt)
+}
+
+/// Tauri command: Replaces a template's name, text, description and defaults. Its scope is kept. Called from frontend prompt editor.
+#[tauri::command]
+pub async fn update_prompt(
+    prompt_id: String,
+    name: String,
+    content: String,
+    description: Option<String>,
+    defaults: Option<BTreeMap<String, String>>,
+) -> Result<PromptTemplate, String> {
+    let name = validate_template(&name, &content)?;
+
+    let mut index = load_prompts_index()?;
+    let prompt = index
+        .prompts
+        .iter_mut()
+        .find(|p| p.id == prompt_id)
+        .ok_or_else(|| format!("Prompt with id '{}' not found", prompt_id))?;
+
+    let variables = template_variables(&content);
+    prompt.name = name;
+    prompt.description = description.filter(|d| !d.trim().is_empty());
+    prompt.content = content;
+    prompt.defaults = retain_used_defaults(defaults.unwrap_or_default(), &variables);
+    prompt.variables = variables;
+    prompt.last_updated_at = chrono::Utc::now().to_rfc3339();
+    let updated = prompt.clone();
+
+    save_prompts_index(&index)?;
+    Ok(updated)
+}
+
+/// Tauri command: Deletes a template. Called from frontend prompt library.
+#[tauri::command]
+pub async fn delete_prompt(prompt_id: String) -> Result<(), String> {
+    let mut index = load_prompts_index()?;
+    let before = index.prompts.len();
+    index.prompts.retain(|p| p.id != prompt_id);
+    if index.prompts.len() == before {
+        return Err(format!("Prompt with id '{}' not found", prompt_id));
+    }
+    save_prompts_index(&index)
+}
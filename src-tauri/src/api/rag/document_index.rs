@@ -0,0 +1,442 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::embeddings::embed_client::{cosine_similarity, embed};
+use crate::api::settings::settings_storage::load_settings;
+use crate::api::workspace::workspace_storage::load_workspaces_index;
+
+/// A chunk of a source document with its line range and embedding vector.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentChunk {
+    pub id: String,
+    pub text: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub vector: Vec<f32>,
+}
+
+/// An indexed source file. `modified_at` (ms since epoch) and `hash` (SHA-256) decide whether it must be re-chunked.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexedDocument {
+    pub path: String,
+    pub modified_at: u64,
+    pub hash: String,
+    pub chunks: Vec<DocumentChunk>,
+}
+
+/// The root structure of a workspace's document index file (.data/rag/<workspace_id>.json). Records the settings the chunks were built with.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentIndex {
+    pub workspace_id: String,
+    pub documents_dir: String,
+    pub embedding_model: String,
+    pub chunk_size: usize,
+    pub chunk_overlap: usize,
+    pub documents: Vec<IndexedDocument>,
+}
+
+/// Summary of an indexing run returned to the frontend.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentIndexReport {
+    pub indexed_files: usize,
+    pub unchanged_files: usize,
+    pub removed_files: usize,
+    pub total_chunks: usize,
+    pub skipped: Vec<String>,
+}
+
+/// A chunk returned by retrieval together with its similarity to the query.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetrievedChunk {
+    pub chunk_id: String,
+    pub source_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+    pub score: f32,
+}
+
+/// File extensions read as plain text. PDFs are handled separately through text extraction.
+const TEXT_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "txt", "rst", "org", "rs", "ts", "tsx", "js", "jsx", "py", "go", "java",
+    "kt", "swift", "c", "h", "cpp", "hpp", "cs", "rb", "php", "sh", "sql", "toml", "yaml", "yml",
+    "json", "html", "css",
+];
+/// Directories that are never descended into while scanning a documents directory.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build"];
+/// Files larger than this are skipped to keep indexing responsive.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Number of chunks sent to Ollama per embed request.
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// Returns the path to the .data/rag directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_rag_dir() -> Result<PathBuf, String> {
+    let rag_dir = PathBuf::from("../.data").join("rag");
+    if !rag_dir.exists() {
+        fs::create_dir_all(&rag_dir)
+            .map_err(|e| format!("Failed to create .data/rag directory: {}", e))?;
+    }
+    Ok(rag_dir)
+}
+
+/// Returns the file path of a workspace's document index.
+fn get_document_index_path(workspace_id: &str) -> Result<PathBuf, String> {
+    let rag_dir = get_rag_dir()?;
+    Ok(rag_dir.join(format!("{}.json", workspace_id)))
+}
+
+/// Loads a workspace's document index, or None if the workspace has never been indexed.
+pub fn load_document_index(workspace_id: &str) -> Result<Option<DocumentIndex>, String> {
+    let path = get_document_index_path(workspace_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read document index: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse document index: {}", e))
+}
+
+/// Saves a workspace's document index. Written compactly since it holds large float vectors.
+pub fn save_document_index(index: &DocumentIndex) -> Result<(), String> {
+    let path = get_document_index_path(&index.workspace_id)?;
+    let content = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize document index: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write document index: {}", e))
+}
+
+/// Deletes a workspace's document index if present. Called when a workspace is deleted.
+pub fn delete_document_index(workspace_id: &str) -> Result<(), String> {
+    let path = get_document_index_path(workspace_id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete document index: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Recursively collects supported files under a directory, skipping hidden and build directories.
+fn collect_document_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory '{}': {}", dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_document_files(&path, files)?;
+            }
+        } else if is_supported_file(&path) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true for PDFs and the plain-text extensions in TEXT_EXTENSIONS.
+fn is_supported_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    extension == "pdf" || TEXT_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// Extracts the text of a document. PDF extraction runs behind catch_unwind because malformed PDFs can panic inside the parser.
+fn extract_text(path: &Path, bytes: &[u8]) -> Result<String, String> {
+    let is_pdf = path
+        .extension()
+        .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case("pdf"));
+
+    if is_pdf {
+        let owned = bytes.to_vec();
+        return std::panic::catch_unwind(move || pdf_extract::extract_text_from_mem(&owned))
+            .map_err(|_| "PDF parser crashed".to_string())?
+            .map_err(|e| format!("Failed to extract PDF text: {}", e));
+    }
+
+    String::from_utf8(bytes.to_vec()).map_err(|_| "File is not valid UTF-8".to_string())
+}
+
+/// Returns the hex-encoded SHA-256 of the given bytes.
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Splits text into line-aligned chunks of roughly `chunk_size` characters, carrying over trailing lines of at least `overlap` characters into the next chunk.
+fn chunk_text(text: &str, chunk_size: usize, overlap: usize) -> Vec<(String, usize, usize)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let mut end = start;
+        let mut size = 0;
+        while end < lines.len() && (size == 0 || size + lines[end].chars().count() < chunk_size) {
+            size += lines[end].chars().count() + 1;
+            end += 1;
+        }
+
+        let chunk = lines[start..end].join("\n");
+        if !chunk.trim().is_empty() {
+            chunks.push((chunk, start + 1, end));
+        }
+        if end >= lines.len() {
+            break;
+        }
+
+        // Step back over trailing lines until the overlap is covered, but always make progress
+        let mut next = end;
+        let mut carried = 0;
+        while next > start + 1 && carried < overlap {
+            next -= 1;
+            carried += lines[next].chars().count() + 1;
+        }
+        start = next.max(start + 1);
+    }
+
+    chunks
+}
+
+/// Returns a file's modification time in milliseconds since the Unix epoch, or 0 if unavailable.
+fn modified_millis(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Embeds chunk texts in batches with the given model.
+async fn embed_chunks(model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(EMBED_BATCH_SIZE) {
+        vectors.extend(embed(model, batch.to_vec(), Some(true), None).await?);
+    }
+    Ok(vectors)
+}
+
+/// Tauri command: Incrementally indexes a workspace's documents directory. Unchanged files (same mtime or hash) keep their chunks; changed files are re-chunked and embedded.
+#[tauri::command]
+pub async fn index_workspace_documents(
+    workspace_id: String,
+) -> Result<DocumentIndexReport, String> {
+    let workspace = load_workspaces_index()?
+        .workspaces
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| format!("Workspace with id '{}' not found", workspace_id))?;
+    let documents_dir = workspace
+        .documents_dir
+        .ok_or_else(|| "This workspace has no documents directory attached".to_string())?;
+    let settings = load_settings()?;
+
+    let root = PathBuf::from(&documents_dir);
+    if !root.is_dir() {
+        return Err(format!(
+            "Documents directory '{}' does not exist",
+            documents_dir
+        ));
+    }
+
+    // Any change to how chunks are built invalidates every stored chunk
+    let previous: HashMap<String, IndexedDocument> = match load_document_index(&workspace_id)? {
+        Some(index)
+            if index.documents_dir == documents_dir
+                && index.embedding_model == settings.embedding_model
+                && index.chunk_size == settings.rag_chunk_size
+                && index.chunk_overlap == settings.rag_chunk_overlap =>
+        {
+            index
+                .documents
+                .into_iter()
+                .map(|d| (d.path.clone(), d))
+                .collect()
+        }
+        _ => HashMap::new(),
+    };
+
+    let mut files = Vec::new();
+    collect_document_files(&root, &mut files)?;
+    files.sort();
+
+    let mut report = DocumentIndexReport {
+        indexed_files: 0,
+        unchanged_files: 0,
+        removed_files: 0,
+        total_chunks: 0,
+        skipped: vec![],
+    };
+    let mut documents = Vec::with_capacity(files.len());
+
+    for file in files {
+        let relative = file
+            .strip_prefix(&root)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let metadata = match fs::metadata(&file) {
+            Ok(m) => m,
+            Err(e) => {
+                report.skipped.push(format!("{}: {}", relative, e));
+                continue;
+            }
+        };
+        if metadata.len() > MAX_FILE_BYTES {
+            report.skipped.push(format!(
+                "{}: larger than {} bytes",
+                relative, MAX_FILE_BYTES
+            ));
+            continue;
+        }
+        let modified_at = modified_millis(&metadata);
+
+        if let Some(existing) = previous.get(&relative) {
+            if existing.modified_at == modified_at {
+                report.unchanged_files += 1;
+                documents.push(existing.clone());
+                continue;
+            }
+        }
+
+        let bytes = match fs::read(&file) {
+            Ok(b) => b,
+            Err(e) => {
+                report.skipped.push(format!("{}: {}", relative, e));
+                continue;
+            }
+        };
+        let hash = sha256_hex(&bytes);
+
+        // Touched but unchanged files only need their mtime refreshed
+        if let Some(existing) = previous.get(&relative) {
+            if existing.hash == hash {
+                report.unchanged_files += 1;
+                documents.push(IndexedDocument {
+                    modified_at,
+                    ..existing.clone()
+                });
+                continue;
+            }
+        }
+
+        let text = match extract_text(&file, &bytes) {
+            Ok(t) => t,
+            Err(e) => {
+                report.skipped.push(format!("{}: {}", relative, e));
+                continue;
+            }
+        };
+
+        let pieces = chunk_text(&text, settings.rag_chunk_size, settings.rag_chunk_overlap);
+        let texts: Vec<String> = pieces.iter().map(|(t, _, _)| t.clone()).collect();
+        let vectors = match embed_chunks(&settings.embedding_model, &texts).await {
+            Ok(v) => v,
+            Err(e) => {
+                // Keep the previous version, if any; its old mtime makes the next run retry this file
+                report
+                    .skipped
+                    .push(format!("{}: embedding failed: {}", relative, e));
+                if let Some(existing) = previous.get(&relative) {
+                    documents.push(existing.clone());
+                }
+                continue;
+            }
+        };
+
+        let chunks = pieces
+            .into_iter()
+            .zip(vectors)
+            .enumerate()
+            .map(
+                |(i, ((text, start_line, end_line), vector))| DocumentChunk {
+                    id: format!("{}#{}", relative, i),
+                    text,
+                    start_line,
+                    end_line,
+                    vector,
+                },
+            )
+            .collect();
+
+        report.indexed_files += 1;
+        documents.push(IndexedDocument {
+            path: relative,
+            modified_at,
+            hash,
+            chunks,
+        });
+    }
+
+    report.removed_files = previous
+        .keys()
+        .filter(|path| !documents.iter().any(|d| &d.path == *path))
+        .count();
+    report.total_chunks = documents.iter().map(|d| d.chunks.len()).sum();
+
+    save_document_index(&DocumentIndex {
+        workspace_id,
+        documents_dir,
+        embedding_model: settings.embedding_model,
+        chunk_size: settings.rag_chunk_size,
+        chunk_overlap: settings.rag_chunk_overlap,
+        documents,
+    })?;
+
+    Ok(report)
+}
+
+/// Returns the top-k chunks of a workspace's document index most similar to the query. Used by send_chat_message for RAG-enabled chats.
+pub async fn retrieve_chunks(
+    workspace_id: &str,
+    query: &str,
+    top_k: usize,
+) -> Result<Vec<RetrievedChunk>, String> {
+    let index = load_document_index(workspace_id)?.ok_or_else(|| {
+        "This workspace's documents have not been indexed yet. Index them before using RAG."
+            .to_string()
+    })?;
+
+    let query_vector = embed(
+        &index.embedding_model,
+        vec![query.to_string()],
+        Some(true),
+        None,
+    )
+    .await?
+    .into_iter()
+    .next()
+    .unwrap_or_default();
+
+    let mut scored: Vec<RetrievedChunk> = index
+        .documents
+        .iter()
+        .flat_map(|d| {
+            d.chunks.iter().map(|c| RetrievedChunk {
+                chunk_id: c.id.clone(),
+                source_path: d.path.clone(),
+                start_line: c.start_line,
+                end_line: c.end_line,
+                text: c.text.clone(),
+                score: cosine_similarity(&query_vector, &c.vector),
+            })
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scored.truncate(top_k);
+    Ok(scored)
+}
@@ -0,0 +1 @@
+pub mod document_index;
@@ -0,0 +1,430 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::api::chats::chat_storage::{load_chat_data, load_chats_index, ChatData, ChatMeta};
+
+/// A single occurrence record in the inverted index: which message of which chat contains a term, and how often.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Posting {
+    pub chat_id: String,
+    pub message_index: usize,
+    pub term_frequency: u32,
+}
+
+/// The root structure for the search index file (search_index.json). Maps each term to its postings and tracks per-message token counts for ranking.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchIndex {
+    pub terms: BTreeMap<String, Vec<Posting>>,
+    pub message_lengths: BTreeMap<String, Vec<usize>>,
+}
+
+/// Optional filters applied to message search results. All fields are optional; dates accept RFC3339 or YYYY-MM-DD.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MessageSearchFilters {
+    pub workspace_id: Option<String>,
+    pub folder_id: Option<String>,
+    pub model: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+}
+
+/// A piece of a search snippet. Highlighted segments are the parts that matched a query term.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnippetSegment {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+/// A single message-level search hit with chat context, snippet segments and relevance score.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageSearchResult {
+    pub chat_id: String,
+    pub chat_title: String,
+    pub workspace_id: String,
+    pub folder_id: Option<String>,
+    pub model_used: String,
+    pub message_index: usize,
+    pub role: String,
+    pub snippet: Vec<SnippetSegment>,
+    pub score: f64,
+}
+
+/// Number of characters of context shown before the first match in a snippet.
+const SNIPPET_CONTEXT_BEFORE: usize = 60;
+/// Maximum number of characters shown after the start of the first match in a snippet.
+const SNIPPET_CONTEXT_AFTER: usize = 140;
+/// Default number of results returned by search_messages when no limit is given.
+const DEFAULT_RESULT_LIMIT: usize = 50;
+
+/// Serializes access to search_index.json. Chat saves from parallel streams, auto-titling and summaries would otherwise overwrite each other's postings.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Returns the path to the .data directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create .data directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the search_index.json file. Used internally for loading/saving the search index.
+fn get_search_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("search_index.json"))
+}
+
+/// Splits text into lowercase alphanumeric terms together with their byte ranges in the original text.
+fn tokenize_with_spans(text: &str) -> Vec<(String, usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (pos, ch) in text.char_indices() {
+        if ch.is_alphanumeric() {
+            if start.is_none() {
+                start = Some(pos);
+            }
+        } else if let Some(s) = start.take() {
+            tokens.push((text[s..pos].to_lowercase(), s, pos));
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((text[s..].to_lowercase(), s, text.len()));
+    }
+
+    tokens
+}
+
+/// Splits text into lowercase alphanumeric terms. Used both for indexing messages and for parsing queries.
+fn tokenize(text: &str) -> Vec<String> {
+    tokenize_with_spans(text)
+        .into_iter()
+        .map(|(term, _, _)| term)
+        .collect()
+}
+
+/// Removes every posting and length entry belonging to the given chat from an in-memory index.
+fn remove_chat_postings(index: &mut SearchIndex, chat_id: &str) {
+    if index.message_lengths.remove(chat_id).is_none() {
+        return;
+    }
+    index.terms.retain(|_, postings| {
+        postings.retain(|p| p.chat_id != chat_id);
+        !postings.is_empty()
+    });
+}
+
+/// Adds postings for every message of a chat to an in-memory index.
+fn add_chat_postings(index: &mut SearchIndex, chat_id: &str, data: &ChatData) {
+    let mut lengths = Vec::with_capacity(data.messages.len());
+
+    for (message_index, message) in data.messages.iter().enumerate() {
+        let tokens = tokenize(&message.content);
+        lengths.push(tokens.len());
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for token in tokens {
+            *frequencies.entry(token).or_insert(0) += 1;
+        }
+
+        for (term, term_frequency) in frequencies {
+            index.terms.entry(term).or_default().push(Posting {
+                chat_id: chat_id.to_string(),
+                message_index,
+                term_frequency,
+            });
+        }
+    }
+
+    index.message_lengths.insert(chat_id.to_string(), lengths);
+}
+
+/// Builds a fresh search index from every chat in the chats index. Used on first run and by rebuild_search_index.
+fn build_search_index() -> Result<SearchIndex, String> {
+    let chats_index = load_chats_index()?;
+    let mut index = SearchIndex::default();
+
+    for chat in &chats_index.chats {
+        let data = load_chat_data(&chat.id)?;
+        add_chat_postings(&mut index, &chat.id, &data);
+    }
+
+    Ok(index)
+}
+
+/// Loads the search index from search_index.json, building it from existing chats if it doesn't exist yet. Callers must hold INDEX_LOCK.
+fn load_search_index() -> Result<SearchIndex, String> {
+    let index_path = get_search_index_path()?;
+    if !index_path.exists() {
+        let index = build_search_index()?;
+        save_search_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read search index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse search index: {}", e))
+}
+
+/// Saves the search index to search_index.json. Written compactly since the file grows with every message. Callers must hold INDEX_LOCK.
+fn save_search_index(index: &SearchIndex) -> Result<(), String> {
+    let index_path = get_search_index_path()?;
+    let content = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize search index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write search index: {}", e))
+}
+
+/// Re-indexes all messages of a chat, replacing any previous postings. Called by save_chat_data whenever a chat file is written.
+pub fn index_chat(chat_id: &str, data: &ChatData) -> Result<(), String> {
+    index_chats(&[(chat_id, data)])
+}
+
+/// Re-indexes many chats with a single load and save of the index. Used by callers that write many chat files at once, such as imports.
+pub fn index_chats(chats: &[(&str, &ChatData)]) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut index = load_search_index()?;
+    for (chat_id, data) in chats {
+        remove_chat_postings(&mut index, chat_id);
+        add_chat_postings(&mut index, chat_id, data);
+    }
+    save_search_index(&index)
+}
+
+/// Removes the given chats from the search index. Called when chats are deleted individually or with their workspace.
+pub fn remove_chats_from_search_index(chat_ids: &[String]) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut index = load_search_index()?;
+    for chat_id in chat_ids {
+        remove_chat_postings(&mut index, chat_id);
+    }
+    save_search_index(&index)
+}
+
+/// Parses a filter date given either as RFC3339 or as a plain YYYY-MM-DD date. End-of-range dates are extended to the end of the day.
+fn parse_filter_date(
+    value: &str,
+    end_of_day: bool,
+) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&chrono::Utc));
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}': expected RFC3339 or YYYY-MM-DD", value))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    time.map(|t| t.and_utc())
+        .ok_or_else(|| format!("Invalid date '{}'", value))
+}
+
+/// Returns true if a chat passes the workspace, folder, model and date-range filters.
+fn chat_matches_filters(
+    chat: &ChatMeta,
+    filters: &MessageSearchFilters,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+) -> bool {
+    if let Some(ref ws) = filters.workspace_id {
+        if &chat.workspace_id != ws {
+            return false;
+        }
+    }
+    if let Some(ref folder) = filters.folder_id {
+        if chat.folder_id.as_ref() != Some(folder) {
+            return false;
+        }
+    }
+    if let Some(ref model) = filters.model {
+        if &chat.model_used != model && !chat.models_used.contains(model) {
+            return false;
+        }
+    }
+    if from.is_some() || to.is_some() {
+        let updated = match chrono::DateTime::parse_from_rfc3339(&chat.last_updated_at) {
+            Ok(dt) => dt.with_timezone(&chrono::Utc),
+            Err(_) => return false,
+        };
+        if from.is_some_and(|f| updated < f) || to.is_some_and(|t| updated > t) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Builds a snippet around the first matching term of a message, split into highlighted and plain segments.
+fn build_snippet(content: &str, query_terms: &HashSet<String>) -> Vec<SnippetSegment> {
+    let matches: Vec<(usize, usize)> = tokenize_with_spans(content)
+        .into_iter()
+        .filter(|(term, _, _)| query_terms.contains(term))
+        .map(|(_, start, end)| (start, end))
+        .collect();
+
+    let first_match = matches.first().map(|(start, _)| *start).unwrap_or(0);
+
+    // Walk back/forward by characters so the window never splits a multibyte character
+    let window_start = content[..first_match]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_BEFORE.saturating_sub(1))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let window_end = content[first_match..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_AFTER)
+        .map(|(i, _)| first_match + i)
+        .unwrap_or(content.len());
+
+    let mut segments = Vec::new();
+    let mut cursor = window_start;
+
+    if window_start > 0 {
+        segments.push(SnippetSegment {
+            text: "…".to_string(),
+            highlighted: false,
+        });
+    }
+
+    for (start, end) in matches {
+        if start < window_start || end > window_end {
+            continue;
+        }
+        if start > cursor {
+            segments.push(SnippetSegment {
+                text: content[cursor..start].to_string(),
+                highlighted: false,
+            });
+        }
+        segments.push(SnippetSegment {
+            text: content[start..end].to_string(),
+            highlighted: true,
+        });
+        cursor = end;
+    }
+
+    if cursor < window_end {
+        segments.push(SnippetSegment {
+            text: content[cursor..window_end].to_string(),
+            highlighted: false,
+        });
+    }
+    if window_end < content.len() {
+        segments.push(SnippetSegment {
+            text: "…".to_string(),
+            highlighted: false,
+        });
+    }
+
+    segments
+}
+
+/// Tauri command: Rebuilds the full-text search index from all chat files. Called from frontend settings if search results look stale.
+#[tauri::command]
+pub async fn rebuild_search_index() -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+    let index = build_search_index()?;
+    save_search_index(&index)
+}
+
+/// Tauri command: Full-text search across message contents. Returns ranked message hits with highlighted snippets, filtered by workspace, folder, model and date range.
+#[tauri::command]
+pub async fn search_messages(
+    query: String,
+    filters: Option<MessageSearchFilters>,
+    limit: Option<usize>,
+) -> Result<Vec<MessageSearchResult>, String> {
+    let query_terms: HashSet<String> = tokenize(&query).into_iter().collect();
+    if query_terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let filters = filters.unwrap_or_default();
+    let from = filters
+        .date_from
+        .as_deref()
+        .map(|d| parse_filter_date(d, false))
+        .transpose()?;
+    let to = filters
+        .date_to
+        .as_deref()
+        .map(|d| parse_filter_date(d, true))
+        .transpose()?;
+
+    let index = {
+        let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+        load_search_index()?
+    };
+    let chats_index = load_chats_index()?;
+    let chats: HashMap<&str, &ChatMeta> = chats_index
+        .chats
+        .iter()
+        .filter(|c| chat_matches_filters(c, &filters, from, to))
+        .map(|c| (c.id.as_str(), c))
+        .collect();
+
+    let total_messages: usize = index.message_lengths.values().map(|l| l.len()).sum();
+
+    // Score each (chat, message) pair with a length-normalized tf-idf sum over the query terms
+    let mut scores: HashMap<(&str, usize), f64> = HashMap::new();
+    for term in &query_terms {
+        let Some(postings) = index.terms.get(term) else {
+            continue;
+        };
+        let idf = (1.0 + total_messages as f64 / postings.len() as f64).ln();
+
+        for posting in postings {
+            if !chats.contains_key(posting.chat_id.as_str()) {
+                continue;
+            }
+            let length = index
+                .message_lengths
+                .get(&posting.chat_id)
+                .and_then(|l| l.get(posting.message_index))
+                .copied()
+                .unwrap_or(1)
+                .max(1);
+            *scores
+                .entry((posting.chat_id.as_str(), posting.message_index))
+                .or_insert(0.0) += posting.term_frequency as f64 * idf / (length as f64).sqrt();
+        }
+    }
+
+    let mut ranked: Vec<((&str, usize), f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(limit.unwrap_or(DEFAULT_RESULT_LIMIT));
+
+    let mut loaded: HashMap<&str, ChatData> = HashMap::new();
+    let mut results = Vec::with_capacity(ranked.len());
+
+    for ((chat_id, message_index), score) in ranked {
+        if !loaded.contains_key(chat_id) {
+            loaded.insert(chat_id, load_chat_data(chat_id)?);
+        }
+        // The index can briefly lag behind a chat file; skip hits that no longer exist
+        let Some(message) = loaded[chat_id].messages.get(message_index) else {
+            continue;
+        };
+        let chat = chats[chat_id];
+
+        results.push(MessageSearchResult {
+            chat_id: chat.id.clone(),
+            chat_title: chat.chat_title.clone(),
+            workspace_id: chat.workspace_id.clone(),
+            folder_id: chat.folder_id.clone(),
+            model_used: message
+                .model
+                .clone()
+                .unwrap_or_else(|| chat.model_used.clone()),
+            message_index,
+            role: message.role.clone(),
+            snippet: build_snippet(&message.content, &query_terms),
+            score,
+        });
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,428 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::api::chats::chat_storage::{load_chat_data, load_chats_index, ChatMeta};
+use crate::api::embeddings::embed_client::{cosine_similarity, embed};
+use crate::api::settings::settings_storage::load_settings;
+
+/// The embedding vector of a single chat message.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageEmbedding {
+    pub chat_id: String,
+    pub message_index: usize,
+    pub vector: Vec<f32>,
+}
+
+/// A re-index to a new embedding model that is still running. Vectors are saved here chat by chat and replace the store's once every chat has been processed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingReindex {
+    pub model: String,
+    pub embeddings: Vec<MessageEmbedding>,
+    pub done_chat_ids: Vec<String>,
+}
+
+/// The root structure for the chat embeddings file (chat_embeddings.json). Records which model produced the vectors.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatEmbeddingsStore {
+    pub model: String,
+    pub embeddings: Vec<MessageEmbedding>,
+    #[serde(default)]
+    pub reindex: Option<PendingReindex>,
+}
+
+/// A chat ranked by semantic similarity to a query, with the best-matching message.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SemanticSearchResult {
+    pub chat: ChatMeta,
+    pub score: f32,
+    pub message_index: usize,
+}
+
+/// Default number of chats returned by semantic_search_chats when no limit is given.
+const DEFAULT_RESULT_LIMIT: usize = 20;
+
+/// Serializes read-modify-write cycles on chat_embeddings.json between background embedding tasks. Never held across an await.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+/// Set while a re-index task runs, so only one pass works on the pending re-index at a time.
+static REINDEX_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Set when a re-index is requested; a running pass starts another round if it is set when the pass ends.
+static REINDEX_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns the path to the .data directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create .data directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the chat_embeddings.json file. Used internally for loading/saving message embeddings.
+fn get_embeddings_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("chat_embeddings.json"))
+}
+
+/// Loads the embeddings store, returning an empty store for the given model if the file doesn't exist yet.
+fn load_embeddings_store(default_model: &str) -> Result<ChatEmbeddingsStore, String> {
+    let path = get_embeddings_path()?;
+    if !path.exists() {
+        return Ok(ChatEmbeddingsStore {
+            model: default_model.to_string(),
+            embeddings: vec![],
+            reindex: None,
+        });
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read chat embeddings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse chat embeddings: {}", e))
+}
+
+/// Saves the embeddings store. Written compactly since it holds large float vectors.
+fn save_embeddings_store(store: &ChatEmbeddingsStore) -> Result<(), String> {
+    let path = get_embeddings_path()?;
+    let content = serde_json::to_string(store)
+        .map_err(|e| format!("Failed to serialize chat embeddings: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write chat embeddings: {}", e))
+}
+
+/// Returns a new, empty re-index to the given model.
+fn new_reindex(model: &str) -> PendingReindex {
+    PendingReindex {
+        model: model.to_string(),
+        embeddings: vec![],
+        done_chat_ids: vec![],
+    }
+}
+
+/// Embeds the given (message index, text) pairs and returns (message index, vector) pairs.
+async fn embed_messages(
+    model: &str,
+    pending: Vec<(usize, String)>,
+) -> Result<Vec<(usize, Vec<f32>)>, String> {
+    if pending.is_empty() {
+        return Ok(vec![]);
+    }
+    let (indexes, texts): (Vec<usize>, Vec<String>) = pending.into_iter().unzip();
+    let vectors = embed(model, texts, Some(true), None).await?;
+    Ok(indexes.into_iter().zip(vectors).collect())
+}
+
+/// Appends message vectors of a chat, skipping messages that already have one.
+fn add_embeddings(
+    target: &mut Vec<MessageEmbedding>,
+    chat_id: &str,
+    vectors: Vec<(usize, Vec<f32>)>,
+) {
+    for (message_index, vector) in vectors {
+        let exists = target
+            .iter()
+            .any(|e| e.chat_id == chat_id && e.message_index == message_index);
+        if !exists {
+            target.push(MessageEmbedding {
+                chat_id: chat_id.to_string(),
+                message_index,
+                vector,
+            });
+        }
+    }
+}
+
+/// Embeds the messages of a chat that are not in the store yet and appends them.
+/// While a re-index is running, new vectors also go into it so they survive the switch to the new model.
+async fn embed_new_messages(chat_id: &str) -> Result<(), String> {
+    let settings = load_settings()?;
+    if !settings.semantic_indexing_enabled {
+        return Ok(());
+    }
+    let model = settings.embedding_model;
+
+    let data = load_chat_data(chat_id)?;
+    let embedded: HashSet<usize> = {
+        let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+        let store = load_embeddings_store(&model)?;
+        let current = if store.model == model {
+            Some(&store.embeddings)
+        } else {
+            store
+                .reindex
+                .as_ref()
+                .filter(|r| r.model == model)
+                .map(|r| &r.embeddings)
+        };
+        current
+            .into_iter()
+            .flatten()
+            .filter(|e| e.chat_id == chat_id)
+            .map(|e| e.message_index)
+            .collect()
+    };
+
+    let pending: Vec<(usize, String)> = data
+        .messages
+        .iter()
+        .enumerate()
+        .filter(|(i, m)| !embedded.contains(i) && !m.content.trim().is_empty())
+        .map(|(i, m)| (i, m.content.clone()))
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let vectors = embed_messages(&model, pending).await?;
+
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load_embeddings_store(&model)?;
+    if store.model == model {
+        add_embeddings(&mut store.embeddings, chat_id, vectors.clone());
+    } else if store.reindex.as_ref().is_none_or(|r| r.model != model) {
+        // The store is still on another model; start collecting vectors for the re-index to this one
+        store.reindex = Some(new_reindex(&model));
+    }
+    if let Some(reindex) = store.reindex.as_mut().filter(|r| r.model == model) {
+        add_embeddings(&mut reindex.embeddings, chat_id, vectors);
+    }
+    save_embeddings_store(&store)
+}
+
+/// Re-embeds every chat message with the configured embedding model, saving progress after each chat, and switches the store to the new vectors once all chats are processed.
+/// Resumes an interrupted re-index to the same model. Chats that fail are skipped and returned as "chat_id: error"; they are embedded again the next time they are saved.
+/// Only called from schedule_semantic_reindex, which never runs two passes at once.
+async fn reindex_all_chats() -> Result<Vec<String>, String> {
+    let model = load_settings()?.embedding_model;
+    let done: HashSet<String> = {
+        let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut store = load_embeddings_store(&model)?;
+        if store.reindex.as_ref().is_none_or(|r| r.model != model) {
+            store.reindex = Some(new_reindex(&model));
+            save_embeddings_store(&store)?;
+        }
+        store
+            .reindex
+            .map(|r| r.done_chat_ids.into_iter().collect())
+            .unwrap_or_default()
+    };
+
+    let mut failures = vec![];
+    for chat in load_chats_index()?.chats {
+        if done.contains(&chat.id) {
+            continue;
+        }
+        let vectors = match load_chat_data(&chat.id) {
+            Ok(data) => {
+                let pending: Vec<(usize, String)> = data
+                    .messages
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, m)| !m.content.trim().is_empty())
+                    .map(|(i, m)| (i, m.content.clone()))
+                    .collect();
+                embed_messages(&model, pending).await
+            }
+            Err(e) => Err(e),
+        };
+        let vectors = match vectors {
+            Ok(vectors) => vectors,
+            Err(e) => {
+                failures.push(format!("{}: {}", chat.id, e));
+                continue;
+            }
+        };
+
+        let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut store = load_embeddings_store(&model)?;
+        let Some(reindex) = store.reindex.as_mut().filter(|r| r.model == model) else {
+            return Err(format!(
+                "Re-index to '{}' was replaced by a re-index to another model",
+                model
+            ));
+        };
+        add_embeddings(&mut reindex.embeddings, &chat.id, vectors);
+        if !reindex.done_chat_ids.contains(&chat.id) {
+            reindex.done_chat_ids.push(chat.id.clone());
+        }
+        save_embeddings_store(&store)?;
+    }
+
+    // Chats deleted while their vectors were being computed must not come back
+    let live_ids: HashSet<String> = load_chats_index()?
+        .chats
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load_embeddings_store(&model)?;
+    // The model may have changed again while this pass ran; the newer re-index then takes over
+    if load_settings()?.embedding_model != model {
+        return Ok(failures);
+    }
+    match store.reindex.take() {
+        Some(mut reindex) if reindex.model == model => {
+            reindex.embeddings.retain(|e| live_ids.contains(&e.chat_id));
+            store.model = reindex.model;
+            store.embeddings = reindex.embeddings;
+        }
+        other => store.reindex = other,
+    }
+    save_embeddings_store(&store)?;
+    Ok(failures)
+}
+
+/// Queues a background task that embeds any new messages of a chat. Called by save_chat_data so the chat write itself never waits on Ollama.
+pub fn schedule_chat_embedding(chat_id: &str) {
+    let chat_id = chat_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = embed_new_messages(&chat_id).await {
+            eprintln!("Failed to embed messages for chat {}: {}", chat_id, e);
+        }
+    });
+}
+
+/// Queues one background task that embeds the new messages of many chats in turn. Used after imports and restores so Ollama gets one job instead of one per chat.
+pub fn schedule_chats_embedding(chat_ids: Vec<String>) {
+    if chat_ids.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        for chat_id in chat_ids {
+            if let Err(e) = embed_new_messages(&chat_id).await {
+                eprintln!("Failed to embed messages for chat {}: {}", chat_id, e);
+            }
+        }
+    });
+}
+
+/// Queues a background re-index of all chat embeddings. Called when the embedding model changes.
+/// Only one re-index runs at a time; a request made while one is running starts another pass once it finishes.
+pub fn schedule_semantic_reindex() {
+    REINDEX_REQUESTED.store(true, Ordering::SeqCst);
+    if REINDEX_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            while REINDEX_REQUESTED.swap(false, Ordering::SeqCst) {
+                match reindex_all_chats().await {
+                    Ok(failures) => {
+                        for failure in failures {
+                            eprintln!("Skipped chat while re-indexing embeddings: {}", failure);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to re-index chat embeddings: {}", e),
+                }
+            }
+            REINDEX_RUNNING.store(false, Ordering::SeqCst);
+            // A request made just before the flag was cleared saw the task as running; pick it up here
+            if !REINDEX_REQUESTED.load(Ordering::SeqCst)
+                || REINDEX_RUNNING.swap(true, Ordering::SeqCst)
+            {
+                break;
+            }
+        }
+    });
+}
+
+/// Removes the embeddings of the given chats, including those of a re-index in progress. Called when chats are deleted individually or with their workspace.
+pub fn remove_chats_from_semantic_index(chat_ids: &[String]) -> Result<(), String> {
+    let path = get_embeddings_path()?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load_embeddings_store("")?;
+    store.embeddings.retain(|e| !chat_ids.contains(&e.chat_id));
+    if let Some(reindex) = store.reindex.as_mut() {
+        reindex
+            .embeddings
+            .retain(|e| !chat_ids.contains(&e.chat_id));
+        reindex.done_chat_ids.retain(|id| !chat_ids.contains(id));
+    }
+    save_embeddings_store(&store)
+}
+
+/// Tauri command: Re-embeds all chats in the background with the configured embedding model. Called from frontend settings.
+#[tauri::command]
+pub async fn rebuild_semantic_index() -> Result<(), String> {
+    schedule_semantic_reindex();
+    Ok(())
+}
+
+/// Tauri command: Ranks chats by cosine similarity between the query and their messages. Optionally restricted to one workspace.
+#[tauri::command]
+pub async fn semantic_search_chats(
+    query: String,
+    workspace_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<SemanticSearchResult>, String> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let settings = load_settings()?;
+    let model = settings.embedding_model;
+
+    let query_vector = embed(&model, vec![trimmed.to_string()], Some(true), None)
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+
+    let store = {
+        let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+        load_embeddings_store(&model)?
+    };
+    if store.model != model {
+        return Err(if store.reindex.is_some_and(|r| r.model == model) {
+            format!(
+                "Chat embeddings are being re-indexed from '{}' to '{}'. Try again shortly.",
+                store.model, model
+            )
+        } else {
+            format!(
+                "Chat embeddings were built with '{}'. Rebuild the semantic index to search with '{}'.",
+                store.model, model
+            )
+        });
+    }
+
+    let chats_index = load_chats_index()?;
+    let chats: HashMap<&str, &ChatMeta> = chats_index
+        .chats
+        .iter()
+        .filter(|c| workspace_id.as_ref().is_none_or(|ws| &c.workspace_id == ws))
+        .map(|c| (c.id.as_str(), c))
+        .collect();
+
+    // Each chat is scored by its best-matching message
+    let mut best: HashMap<&str, (f32, usize)> = HashMap::new();
+    for entry in &store.embeddings {
+        if !chats.contains_key(entry.chat_id.as_str()) {
+            continue;
+        }
+        let score = cosine_similarity(&query_vector, &entry.vector);
+        let slot = best
+            .entry(entry.chat_id.as_str())
+            .or_insert((f32::MIN, entry.message_index));
+        if score > slot.0 {
+            *slot = (score, entry.message_index);
+        }
+    }
+
+    let mut results: Vec<SemanticSearchResult> = best
+        .into_iter()
+        .map(|(chat_id, (score, message_index))| SemanticSearchResult {
+            chat: chats[chat_id].clone(),
+            score,
+            message_index,
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit.unwrap_or(DEFAULT_RESULT_LIMIT));
+
+    Ok(results)
+}
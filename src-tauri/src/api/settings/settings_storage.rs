@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Context window the chat history is fitted to when neither the settings nor a preset set one. Matches Ollama's default num_ctx.
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 4096;
+
+/// How the chat history is shortened when it no longer fits the model's context window.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextStrategy {
+    /// Drop the oldest messages until the rest fits.
+    #[default]
+    SlidingWindow,
+    /// Keep system messages and the last `context_keep_last` messages.
+    KeepLast,
+    /// Replace older messages with a model-written summary pinned to the top of the history.
+    Summarize,
+}
+
+/// Application-wide settings stored in settings.json. Missing fields fall back to their defaults so older files keep loading.
+/// `context_window` is only sent to Ollama as num_ctx when set; otherwise the model runs with its own default.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppSettings {
+    pub embedding_model: String,
+    pub semantic_indexing_enabled: bool,
+    pub rag_top_k: usize,
+    pub rag_chunk_size: usize,
+    pub rag_chunk_overlap: usize,
+    pub trash_retention_days: u32,
+    pub auto_title_enabled: bool,
+    pub title_model: Option<String>,
+    pub context_window: Option<u32>,
+    pub context_response_reserve: u32,
+    pub context_strategy: ContextStrategy,
+    pub context_keep_last: usize,
+    pub context_exact_token_count: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            embedding_model: "nomic-embed-text".to_string(),
+            semantic_indexing_enabled: true,
+            rag_top_k: 4,
+            rag_chunk_size: 1500,
+            rag_chunk_overlap: 200,
+            trash_retention_days: 30,
+            auto_title_enabled: true,
+            title_model: None,
+            context_window: None,
+            context_response_reserve: 1024,
+            context_strategy: ContextStrategy::SlidingWindow,
+            context_keep_last: 20,
+            context_exact_token_count: false,
+        }
+    }
+}
+
+/// Returns the path to the .data directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create .data directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the settings.json file. Used internally for loading/saving settings.
+fn get_settings_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("settings.json"))
+}
+
+/// Loads settings from settings.json, creating it with defaults if it doesn't exist. Used by any feature with user-configurable behaviour.
+pub fn load_settings() -> Result<AppSettings, String> {
+    let settings_path = get_settings_path()?;
+    if !settings_path.exists() {
+        let settings = AppSettings::default();
+        save_settings(&settings)?;
+        return Ok(settings);
+    }
+    let content = fs::read_to_string(&settings_path)
+        .map_err(|e| format!("Failed to read settings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
+}
+
+/// Saves settings to settings.json. Used by update_settings.
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let settings_path = get_settings_path()?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&settings_path, content).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+/// Tauri command: Returns the current application settings. Called from frontend settings screens.
+#[tauri::command]
+pub async fn get_settings() -> Result<AppSettings, String> {
+    load_settings()
+}
+
+/// Tauri command: Replaces the application settings. Changing the embedding model triggers a background re-index of chat embeddings.
+#[tauri::command]
+pub async fn update_settings(settings: AppSettings) -> Result<AppSettings, String> {
+    let embedding_model = settings.embedding_model.trim();
+    if embedding_model.is_empty() {
+        return Err("Embedding model cannot be empty".to_string());
+    }
+
+    if settings.rag_chunk_size == 0 {
+        return Err("RAG chunk size must be greater than zero".to_string());
+    }
+    if settings.rag_chunk_overlap >= settings.rag_chunk_size {
+        return Err("RAG chunk overlap must be smaller than the chunk size".to_string());
+    }
+
+    if settings.context_response_reserve
+        >= settings.context_window.unwrap_or(DEFAULT_CONTEXT_WINDOW)
+    {
+        return Err("Context response reserve must be smaller than the context window".to_string());
+    }
+    if settings.context_keep_last == 0 {
+        return Err("Context must keep at least one message".to_string());
+    }
+
+    let previous = load_settings()?;
+    let title_model = settings
+        .title_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(String::from);
+    let settings = AppSettings {
+        embedding_model: embedding_model.to_string(),
+        title_model,
+        ..settings
+    };
+    save_settings(&settings)?;
+
+    let reindex_needed = settings.semantic_indexing_enabled
+        && (previous.embedding_model != settings.embedding_model
+            || !previous.semantic_indexing_enabled);
+    if reindex_needed {
+        crate::api::search::semantic_index::schedule_semantic_reindex();
+    }
+
+    Ok(settings)
+}
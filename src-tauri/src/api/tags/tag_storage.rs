@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::chats::chat_storage::load_chats_index;
+use crate::api::folders::folders_storage::load_folders_index;
+
+/// Longest tag accepted, in characters.
+const MAX_TAG_LENGTH: usize = 40;
+
+/// How often a tag is used in a workspace, split by folders and chats.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagCount {
+    pub tag: String,
+    pub folder_count: usize,
+    pub chat_count: usize,
+}
+
+/// Trims and lowercases a tag so "Rust " and "rust" are the same tag. Rejects empty and overly long tags.
+pub fn normalize_tag(tag: &str) -> Result<String, String> {
+    let normalized = tag.trim().to_lowercase();
+    if normalized.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    if normalized.chars().count() > MAX_TAG_LENGTH {
+        return Err(format!(
+            "Tag cannot be longer than {} characters",
+            MAX_TAG_LENGTH
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Adds a normalized tag to a tag list, keeping it sorted. Returns false if the tag was already there.
+pub fn insert_tag(tags: &mut Vec<String>, tag: String) -> bool {
+    match tags.binary_search(&tag) {
+        Ok(_) => false,
+        Err(position) => {
+            tags.insert(position, tag);
+            true
+        }
+    }
+}
+
+/// Returns true if `tags` contains every tag in `filter`. An empty or missing filter matches everything. Used by search_chats and get_folders_for_workspace.
+pub fn has_all_tags(tags: &[String], filter: Option<&[String]>) -> bool {
+    filter.is_none_or(|wanted| {
+        wanted
+            .iter()
+            .all(|w| tags.iter().any(|t| t == &w.trim().to_lowercase()))
+    })
+}
+
+/// Returns the running count for a tag, starting it at zero.
+fn tag_count<'a>(counts: &'a mut BTreeMap<String, TagCount>, tag: &str) -> &'a mut TagCount {
+    counts.entry(tag.to_string()).or_insert_with(|| TagCount {
+        tag: tag.to_string(),
+        folder_count: 0,
+        chat_count: 0,
+    })
+}
+
+/// Tauri command: Returns every tag used by the folders and chats of a workspace with how often it is used, most used first. Called from frontend tag filter.
+#[tauri::command]
+pub async fn list_workspace_tags(workspace_id: String) -> Result<Vec<TagCount>, String> {
+    let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+
+    for folder in load_folders_index()?
+        .folders
+        .iter()
+        .filter(|f| f.workspace_id == workspace_id)
+    {
+        for tag in &folder.tags {
+            tag_count(&mut counts, tag).folder_count += 1;
+        }
+    }
+    for chat in load_chats_index()?
+        .chats
+        .iter()
+        .filter(|c| c.workspace_id == workspace_id)
+    {
+        for tag in &chat.tags {
+            tag_count(&mut counts, tag).chat_count += 1;
+        }
+    }
+
+    let mut tags: Vec<TagCount> = counts.into_values().collect();
+    tags.sort_by(|a, b| {
+        (b.folder_count + b.chat_count)
+            .cmp(&(a.folder_count + a.chat_count))
+            .then_with(|| a.tag.cmp(&b.tag))
+    });
+    Ok(tags)
+}
@@ -0,0 +1 @@
+pub mod trash_storage;
@@ -0,0 +1,435 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::api::chats::chat_storage::{
+    get_chat_file_path, insert_chats, load_chats_index, save_chats_index, ChatData, ChatMeta,
+};
+use crate::api::folders::folders_storage::{load_folders_index, save_folders_index, FolderMeta};
+use crate::api::rag::document_index::delete_document_index;
+use crate::api::settings::settings_storage::load_settings;
+use crate::api::workspace::workspace_storage::{
+    load_workspaces_index, save_workspaces_index, WorkspaceMeta,
+};
+
+/// The kind of entity a trash item was created for.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashItemKind {
+    Chat,
+    Folder,
+    Workspace,
+}
+
+/// A deleted chat, folder or workspace together with everything needed to restore it. Chat files live in .data/trash/chats until restored or purged.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashItem {
+    pub id: String,
+    pub kind: TrashItemKind,
+    pub name: String,
+    pub deleted_at: String,
+    pub workspace: Option<WorkspaceMeta>,
+    pub folders: Vec<FolderMeta>,
+    pub chats: Vec<ChatMeta>,
+}
+
+/// The root structure for the trash index file (trash.json). Contains list of all TrashItem entries.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashIndex {
+    pub items: Vec<TrashItem>,
+}
+
+/// Returns the path to the .data directory, creating it if it doesn't exist. Used internally for all file operations.
+fn get_data_dir() -> Result<PathBuf, String> {
+    let data_dir = PathBuf::from("../.data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create .data directory: {}", e))?;
+    }
+    let trash_dir = data_dir.join("trash").join("chats");
+    if !trash_dir.exists() {
+        fs::create_dir_all(&trash_dir)
+            .map_err(|e| format!("Failed to create .data/trash/chats directory: {}", e))?;
+    }
+    Ok(data_dir)
+}
+
+/// Returns the path to the trash.json index file. Used internally for loading/saving the trash.
+fn get_trash_index_path() -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join("trash.json"))
+}
+
+/// Returns the path a trashed chat's data file is kept at.
+fn get_trashed_chat_path(chat_id: &str) -> Result<PathBuf, String> {
+    let data_dir = get_data_dir()?;
+    Ok(data_dir
+        .join("trash")
+        .join("chats")
+        .join(format!("{}.json", chat_id)))
+}
+
+/// Returns the current UTC time as an ISO 8601 RFC3339 string. Used for the deleted_at timestamp.
+fn now_iso() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Loads the trash index from trash.json, creating it with an empty list if it doesn't exist.
+pub fn load_trash_index() -> Result<TrashIndex, String> {
+    let index_path = get_trash_index_path()?;
+    if !index_path.exists() {
+        let index = TrashIndex { items: vec![] };
+        save_trash_index(&index)?;
+        return Ok(index);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read trash index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse trash index: {}", e))
+}
+
+/// Saves the trash index to trash.json.
+pub fn save_trash_index(index: &TrashIndex) -> Result<(), String> {
+    let index_path = get_trash_index_path()?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize trash index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write trash index: {}", e))
+}
+
+/// Moves a chat's data file into the trash directory. A missing source file is not an error.
+pub fn stash_chat_file(chat_id: &str) -> Result<(), String> {
+    let source = get_chat_file_path(chat_id)?;
+    if !source.exists() {
+        return Ok(());
+    }
+    fs::rename(&source, get_trashed_chat_path(chat_id)?)
+        .map_err(|e| format!("Failed to move chat '{}' to trash: {}", chat_id, e))
+}
+
+/// Moves a stashed chat file back out of the trash directory. Used to undo stash_chat_file when a delete fails part-way.
+pub fn unstash_chat_file(chat_id: &str) -> Result<(), String> {
+    let source = get_trashed_chat_path(chat_id)?;
+    if !source.exists() {
+        return Ok(());
+    }
+    fs::rename(&source, get_chat_file_path(chat_id)?)
+        .map_err(|e| format!("Failed to move chat '{}' out of the trash: {}", chat_id, e))
+}
+
+/// Moves the data files of several chats into the trash directory. If one fails, the files moved so far are put back.
+pub fn stash_chat_files(chat_ids: &[String]) -> Result<(), String> {
+    for (i, chat_id) in chat_ids.iter().enumerate() {
+        if let Err(e) = stash_chat_file(chat_id) {
+            for stashed in &chat_ids[..i] {
+                let _ = unstash_chat_file(stashed);
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Records a deleted entity in the trash and returns the new item's ID. Called by delete_chat, delete_folder and delete_workspace before they remove it from the live indexes.
+pub fn add_trash_item(
+    kind: TrashItemKind,
+    name: String,
+    workspace: Option<WorkspaceMeta>,
+    folders: Vec<FolderMeta>,
+    chats: Vec<ChatMeta>,
+) -> Result<String, String> {
+    let mut index = load_trash_index()?;
+    let id = uuid::Uuid::new_v4().to_string();
+    index.items.push(TrashItem {
+        id: id.clone(),
+        kind,
+        name,
+        deleted_at: now_iso(),
+        workspace,
+        folders,
+        chats,
+    });
+    save_trash_index(&index)?;
+    Ok(id)
+}
+
+/// Records several deleted chats as one trash item each, with a single save of the trash index, and returns the new item IDs. Used by bulk deletion and the integrity repair.
+pub fn add_chat_trash_items(chats: Vec<ChatMeta>) -> Result<Vec<String>, String> {
+    let mut index = load_trash_index()?;
+    let deleted_at = now_iso();
+    let mut ids = Vec::with_capacity(chats.len());
+    for chat in chats {
+        let id = uuid::Uuid::new_v4().to_string();
+        ids.push(id.clone());
+        index.items.push(TrashItem {
+            id,
+            kind: TrashItemKind::Chat,
+            name: chat.chat_title.clone(),
+            deleted_at: deleted_at.clone(),
+            workspace: None,
+            folders: vec![],
+            chats: vec![chat],
+        });
+    }
+    save_trash_index(&index)?;
+    Ok(ids)
+}
+
+/// Undoes the trash side of a delete that failed before the live indexes were updated: drops the trash items and moves the chat files back.
+/// Best effort, since it already runs on an error path; failures are logged.
+pub fn undo_trash(item_ids: &[String], chat_ids: &[String]) {
+    let dropped = load_trash_index().and_then(|mut index| {
+        index.items.retain(|item| !item_ids.contains(&item.id));
+        save_trash_index(&index)
+    });
+    if let Err(e) = dropped {
+        eprintln!("Failed to remove trash items of a failed delete: {}", e);
+    }
+    for chat_id in chat_ids {
+        if let Err(e) = unstash_chat_file(chat_id) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Permanently deletes the files held by a trash item.
+fn delete_trashed_files(item: &TrashItem) -> Result<(), String> {
+    for chat in &item.chats {
+        let path = get_trashed_chat_path(&chat.id)?;
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete trashed chat '{}': {}", chat.id, e))?;
+        }
+    }
+    if let Some(ref workspace) = item.workspace {
+        delete_document_index(&workspace.id)?;
+    }
+    Ok(())
+}
+
+/// Reads trashed chats back for restoring. Fails if any ID is already taken, before anything is written.
+fn load_trashed_chats(chats: &[ChatMeta]) -> Result<Vec<(ChatMeta, ChatData)>, String> {
+    let chats_index = load_chats_index()?;
+    let mut restored = Vec::with_capacity(chats.len());
+
+    for chat in chats {
+        if chats_index.chats.iter().any(|c| c.id == chat.id) {
+            return Err(format!("A chat with id '{}' already exists", chat.id));
+        }
+        let path = get_trashed_chat_path(&chat.id)?;
+        let data: ChatData = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read trashed chat: {}", e))?;
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse trashed chat: {}", e))?
+        } else {
+            ChatData::default()
+        };
+        restored.push((chat.clone(), data));
+    }
+    Ok(restored)
+}
+
+/// Puts chats read by load_trashed_chats back into the chats index and removes their files from the trash.
+fn insert_restored_chats(restored: Vec<(ChatMeta, ChatData)>) -> Result<(), String> {
+    let ids: Vec<String> = restored.iter().map(|(c, _)| c.id.clone()).collect();
+    insert_chats(restored)?;
+
+    for id in &ids {
+        let path = get_trashed_chat_path(id)?;
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to clean up trashed chat '{}': {}", id, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Restores a trashed chat into its workspace, and back into its folder if the folder still exists.
+fn restore_chat_item(item: &TrashItem) -> Result<(), String> {
+    let workspaces = load_workspaces_index()?;
+    let mut folders_index = load_folders_index()?;
+
+    let mut chats = item.chats.clone();
+    for chat in &mut chats {
+        if !workspaces
+            .workspaces
+            .iter()
+            .any(|w| w.id == chat.workspace_id)
+        {
+            return Err(format!(
+                "The workspace of chat '{}' no longer exists. Restore the workspace first.",
+                chat.chat_title
+            ));
+        }
+        let folder = chat
+            .folder_id
+            .as_ref()
+            .and_then(|fid| folders_index.folders.iter_mut().find(|f| &f.id == fid));
+        match folder {
+            Some(folder) => {
+                if !folder.chat_ids.contains(&chat.id) {
+                    folder.chat_ids.push(chat.id.clone());
+                }
+            }
+            None => chat.folder_id = None,
+        }
+    }
+
+    insert_restored_chats(load_trashed_chats(&chats)?)?;
+    save_folders_index(&folders_index)
+}
+
+/// Restores a trashed folder with its subfolders and moves their former chats back if they are still unfiled in the same workspace.
+fn restore_folder_item(item: &TrashItem) -> Result<(), String> {
+    let workspaces = load_workspaces_index()?;
+    let mut folders_index = load_folders_index()?;
+    let mut chats_index = load_chats_index()?;
+
+    for folder in &item.folders {
+        if !workspaces
+            .workspaces
+            .iter()
+            .any(|w| w.id == folder.workspace_id)
+        {
+            return Err(format!(
+                "The workspace of folder '{}' no longer exists. Restore the workspace first.",
+                folder.name
+            ));
+        }
+        if folders_index.folders.iter().any(|f| f.id == folder.id) {
+            return Err(format!("A folder with id '{}' already exists", folder.id));
+        }
+
+        let mut restored = folder.clone();
+        restored.chat_ids.retain(|chat_id| {
+            match chats_index.chats.iter_mut().find(|c| &c.id == chat_id) {
+                Some(chat)
+                    if chat.workspace_id == folder.workspace_id && chat.folder_id.is_none() =>
+                {
+                    chat.folder_id = Some(folder.id.clone());
+                    true
+                }
+                _ => false,
+            }
+        });
+        folders_index.folders.push(restored);
+    }
+
+    // A subfolder whose parent is not around any more goes to the top level
+    let ids: Vec<String> = folders_index.folders.iter().map(|f| f.id.clone()).collect();
+    for folder in &mut folders_index.folders {
+        if folder.parent_id.as_ref().is_some_and(|p| !ids.contains(p)) {
+            folder.parent_id = None;
+        }
+    }
+
+    save_folders_index(&folders_index)?;
+    save_chats_index(&chats_index)
+}
+
+/// Restores a trashed workspace with all of its folders and chats.
+fn restore_workspace_item(item: &TrashItem) -> Result<(), String> {
+    let workspace = item
+        .workspace
+        .clone()
+        .ok_or_else(|| "Trash item has no workspace data".to_string())?;
+
+    let mut workspaces = load_workspaces_index()?;
+    if workspaces.workspaces.iter().any(|w| w.id == workspace.id) {
+        return Err(format!(
+            "A workspace with id '{}' already exists",
+            workspace.id
+        ));
+    }
+    let mut folders_index = load_folders_index()?;
+    if let Some(f) = item.folders.iter().find(|f| {
+        folders_index
+            .folders
+            .iter()
+            .any(|existing| existing.id == f.id)
+    }) {
+        return Err(format!("A folder with id '{}' already exists", f.id));
+    }
+    // Chat conflicts must fail here too, or the workspace would come back without its chats
+    let restored_chats = load_trashed_chats(&item.chats)?;
+
+    workspaces.workspaces.push(workspace);
+    save_workspaces_index(&workspaces)?;
+    folders_index.folders.extend(item.folders.iter().cloned());
+    save_folders_index(&folders_index)?;
+    insert_restored_chats(restored_chats)
+}
+
+/// Permanently deletes trash items older than the configured retention period. Runs at startup; a retention of 0 days keeps items forever.
+pub fn purge_expired_trash() -> Result<usize, String> {
+    let retention_days = load_settings()?.trash_retention_days;
+    if retention_days == 0 {
+        return Ok(0);
+    }
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days as i64);
+
+    let mut index = load_trash_index()?;
+    let (expired, kept): (Vec<TrashItem>, Vec<TrashItem>) =
+        index.items.into_iter().partition(|item| {
+            chrono::DateTime::parse_from_rfc3339(&item.deleted_at)
+                .map(|dt| dt < cutoff)
+                .unwrap_or(false)
+        });
+
+    for item in &expired {
+        delete_trashed_files(item)?;
+    }
+    index.items = kept;
+    save_trash_index(&index)?;
+    Ok(expired.len())
+}
+
+/// Tauri command: Returns all items in the trash, newest first. Called from frontend trash view.
+#[tauri::command]
+pub async fn list_trash() -> Result<Vec<TrashItem>, String> {
+    let mut items = load_trash_index()?.items;
+    items.reverse();
+    Ok(items)
+}
+
+/// Tauri command: Restores a trashed chat, folder or workspace with its folder membership and workspace relationships. Called from frontend trash view.
+#[tauri::command]
+pub async fn restore_from_trash(trash_id: String) -> Result<(), String> {
+    let mut index = load_trash_index()?;
+    let position = index
+        .items
+        .iter()
+        .position(|i| i.id == trash_id)
+        .ok_or_else(|| format!("Trash item with id '{}' not found", trash_id))?;
+
+    let item = &index.items[position];
+    match item.kind {
+        TrashItemKind::Chat => restore_chat_item(item)?,
+        TrashItemKind::Folder => restore_folder_item(item)?,
+        TrashItemKind::Workspace => restore_workspace_item(item)?,
+    }
+
+    index.items.remove(position);
+    save_trash_index(&index)
+}
+
+/// Tauri command: Permanently deletes one trash item, or empties the whole trash when no ID is given. Called from frontend trash view.
+#[tauri::command]
+pub async fn purge_trash(trash_id: Option<String>) -> Result<(), String> {
+    let mut index = load_trash_index()?;
+
+    let (purged, kept): (Vec<TrashItem>, Vec<TrashItem>) = match trash_id {
+        Some(ref id) => {
+            if !index.items.iter().any(|i| &i.id == id) {
+                return Err(format!("Trash item with id '{}' not found", id));
+            }
+            index.items.into_iter().partition(|i| &i.id == id)
+        }
+        None => (index.items, vec![]),
+    };
+
+    for item in &purged {
+        delete_trashed_files(item)?;
+    }
+    index.items = kept;
+    save_trash_index(&index)
+}
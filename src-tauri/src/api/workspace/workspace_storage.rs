@@ -2,13 +2,23 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-/// Metadata for a workspace containing id, name, created_at, and last_updated_at timestamps.
+use crate::api::chats::chat_storage::{
+    load_chats_index, remove_from_search_indexes, save_chats_index, ChatMeta,
+};
+use crate::api::folders::folders_storage::{load_folders_index, save_folders_index, FolderMeta};
+use crate::api::trash::trash_storage::{
+    add_trash_item, stash_chat_files, undo_trash, TrashItemKind,
+};
+
+/// Metadata for a workspace containing id, name, optional attached documents directory, created_at, and last_updated_at timestamps.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspaceMeta {
     pub id: String,
     pub name: String,
     pub created_at: String,
     pub last_updated_at: String,
+    #[serde(default)]
+    pub documents_dir: Option<String>,
 }
 
 /// The root structure for the workspaces index file (workspaces.json). Contains list of workspaces and the active workspace ID.
@@ -51,6 +61,7 @@ pub fn load_workspaces_index() -> Result<WorkspacesIndex, String> {
             name: "My Workspace".to_string(),
             created_at: now.clone(),
             last_updated_at: now,
+            documents_dir: None,
         };
         let index = WorkspacesIndex {
             workspaces: vec![default_workspace],
@@ -94,6 +105,7 @@ pub async fn create_workspace(name: String) -> Result<WorkspaceMeta, String> {
         name: trimmed.to_string(),
         created_at: now.clone(),
         last_updated_at: now,
+        documents_dir: None,
     };
 
     let mut index = load_workspaces_index()?;
@@ -126,7 +138,38 @@ pub async fn rename_workspace(workspace_id: String, new_name: String) -> Result<
     save_workspaces_index(&index)
 }
 
-/// Tauri command: Deletes a workspace and cleans up its folders and chats. Prevents deletion of last workspace. Called from frontend when user deletes a workspace.
+/// Tauri command: Attaches a local documents directory to a workspace for retrieval-augmented chat, or detaches it when None. Called from frontend workspace settings.
+#[tauri::command]
+pub async fn set_workspace_documents_dir(
+    workspace_id: String,
+    documents_dir: Option<String>,
+) -> Result<(), String> {
+    let documents_dir = match documents_dir.as_deref().map(str::trim) {
+        Some(dir) if !dir.is_empty() => {
+            if !PathBuf::from(dir).is_dir() {
+                return Err(format!("Documents directory '{}' does not exist", dir));
+            }
+            Some(dir.to_string())
+        }
+        _ => None,
+    };
+
+    let mut index = load_workspaces_index()?;
+    let now = now_iso();
+
+    let workspace = index
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| format!("Workspace with id '{}' not found", workspace_id))?;
+
+    workspace.documents_dir = documents_dir;
+    workspace.last_updated_at = now;
+
+    save_workspaces_index(&index)
+}
+
+/// Tauri command: Moves a workspace with its folders and chats to the trash. Prevents deletion of last workspace. Called from frontend when user deletes a workspace.
 #[tauri::command]
 pub async fn delete_workspace(workspace_id: String) -> Result<(), String> {
     let mut index = load_workspaces_index()?;
@@ -143,7 +186,7 @@ pub async fn delete_workspace(workspace_id: String) -> Result<(), String> {
         .position(|w| w.id == workspace_id)
         .ok_or_else(|| format!("Workspace with id '{}' not found", workspace_id))?;
 
-    index.workspaces.remove(position);
+    let workspace = index.workspaces.remove(position);
 
     // If the active workspace was deleted, switch to the first available one
     if index.active_workspace_id == workspace_id {
@@ -154,13 +197,55 @@ pub async fn delete_workspace(workspace_id: String) -> Result<(), String> {
             .unwrap_or_default();
     }
 
-    save_workspaces_index(&index)?;
+    // Collect the workspace's folders and chats; nothing is written until they are in the trash
+    let original_folders = load_folders_index()?;
+    let mut folders_index = original_folders.clone();
+    let (folders, kept_folders): (Vec<FolderMeta>, Vec<FolderMeta>) = folders_index
+        .folders
+        .into_iter()
+        .partition(|f| f.workspace_id == workspace_id);
+    folders_index.folders = kept_folders;
+
+    let original_chats = load_chats_index()?;
+    let mut chats_index = original_chats.clone();
+    let (chats, kept_chats): (Vec<ChatMeta>, Vec<ChatMeta>) = chats_index
+        .chats
+        .into_iter()
+        .partition(|c| c.workspace_id == workspace_id);
+    chats_index.chats = kept_chats;
+    let chat_ids: Vec<String> = chats.iter().map(|c| c.id.clone()).collect();
+    // A folder elsewhere may still list one of these chats
+    for folder in &mut folders_index.folders {
+        folder.chat_ids.retain(|id| !chat_ids.contains(id));
+    }
 
-    // Also clean up folders and chats belonging to this workspace
-    // Import and call the cleanup functions
-    crate::api::folders::folders_storage::delete_folders_for_workspace(&workspace_id)?;
-    crate::api::chats::chat_storage::delete_chats_for_workspace(&workspace_id)?;
+    stash_chat_files(&chat_ids)?;
+    let trash_id = match add_trash_item(
+        TrashItemKind::Workspace,
+        workspace.name.clone(),
+        Some(workspace),
+        folders,
+        chats,
+    ) {
+        Ok(id) => id,
+        Err(e) => {
+            undo_trash(&[], &chat_ids);
+            return Err(e);
+        }
+    };
+
+    // The workspaces index goes last, so a failure leaves the workspace in place with its folders and chats
+    let committed = save_chats_index(&chats_index)
+        .and_then(|_| save_folders_index(&folders_index))
+        .and_then(|_| save_workspaces_index(&index));
+    if let Err(e) = committed {
+        let _ = save_chats_index(&original_chats);
+        let _ = save_folders_index(&original_folders);
+        undo_trash(&[trash_id], &chat_ids);
+        return Err(e);
+    }
 
+    remove_from_search_indexes(&chat_ids);
     Ok(())
 }
 
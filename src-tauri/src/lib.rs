@@ -1,18 +1,63 @@
 mod api;
 
+use api::backup::workspace_backup::backup_workspace;
+use api::backup::workspace_backup::restore_workspace;
+use api::chats::chat_bulk::bulk_delete_chats;
+use api::chats::chat_bulk::bulk_export_chats;
+use api::chats::chat_bulk::bulk_move_chats;
+use api::chats::chat_bulk::bulk_tag_chats;
+use api::chats::chat_listing::count_chats;
+use api::chats::chat_listing::list_chats;
+use api::chats::chat_storage::add_chat_tag;
 use api::chats::chat_storage::delete_chat;
+use api::chats::chat_storage::duplicate_chat;
 use api::chats::chat_storage::get_all_chats;
 use api::chats::chat_storage::get_chat_messages;
 use api::chats::chat_storage::get_chats_for_workspace;
+use api::chats::chat_storage::move_chat_to_workspace;
+use api::chats::chat_storage::remove_chat_tag;
 use api::chats::chat_storage::rename_chat;
 use api::chats::chat_storage::search_chats;
+use api::chats::chat_storage::set_chat_archived;
+use api::chats::chat_storage::set_chat_model;
+use api::chats::chat_storage::set_chat_pinned;
+use api::chats::chat_storage::set_chat_rag_enabled;
+use api::chats::chat_storage::set_chat_starred;
+use api::chats::chat_summary::summarize_chat;
 use api::chats::generate_chat_message::send_chat_message;
+use api::comparisons::compare_models::compare_models;
+use api::comparisons::comparison_storage::delete_comparison;
+use api::comparisons::comparison_storage::get_comparison;
+use api::comparisons::comparison_storage::list_comparisons;
+use api::comparisons::comparison_storage::promote_comparison_result;
+use api::completions::completion_storage::delete_completion_session;
+use api::completions::completion_storage::get_completion_session;
+use api::completions::completion_storage::list_completion_sessions;
+use api::completions::completion_storage::rename_completion_session;
+use api::completions::generate_completion::generate_completion;
+use api::embeddings::embed_texts::compute_similarity_matrix;
+use api::embeddings::embed_texts::embed_texts;
+use api::embeddings::embedding_sets::delete_embedding_set;
+use api::embeddings::embedding_sets::get_embedding_set;
+use api::embeddings::embedding_sets::get_embedding_set_similarity;
+use api::embeddings::embedding_sets::list_embedding_sets;
+use api::embeddings::embedding_sets::save_embedding_set;
+use api::export::chat_export::export_chat;
+use api::export::chat_export::export_folder;
+use api::export::chat_export::export_workspace;
 use api::folders::folders_storage::add_chat_to_folder;
+use api::folders::folders_storage::add_folder_tag;
 use api::folders::folders_storage::create_folder;
 use api::folders::folders_storage::delete_folder;
 use api::folders::folders_storage::get_folders_for_workspace;
+use api::folders::folders_storage::move_folder;
+use api::folders::folders_storage::move_folder_to_workspace;
 use api::folders::folders_storage::remove_chat_from_folder_cmd;
+use api::folders::folders_storage::remove_folder_tag;
 use api::folders::folders_storage::rename_folder;
+use api::import::chat_import::import_chats;
+use api::integrity::data_integrity::check_data_integrity;
+use api::integrity::data_integrity::verify_data_integrity;
 use api::models::copy_model::copy_model;
 use api::models::create_model::create_model;
 use api::models::delete_model::delete_model;
@@ -21,16 +66,57 @@ use api::models::list_running_models::list_running_models;
 use api::models::pull_model::pull_model;
 use api::models::push_model::push_model;
 use api::models::show_model_details::show_model_details;
+use api::presets::preset_storage::create_preset;
+use api::presets::preset_storage::delete_preset;
+use api::presets::preset_storage::get_preset;
+use api::presets::preset_storage::list_presets;
+use api::presets::preset_storage::update_preset;
+use api::presets::preset_transfer::export_presets;
+use api::presets::preset_transfer::import_presets;
+use api::prompts::prompt_render::render_prompt;
+use api::prompts::prompt_storage::create_prompt;
+use api::prompts::prompt_storage::delete_prompt;
+use api::prompts::prompt_storage::get_prompt;
+use api::prompts::prompt_storage::list_prompts;
+use api::prompts::prompt_storage::update_prompt;
+use api::rag::document_index::index_workspace_documents;
+use api::search::search_index::rebuild_search_index;
+use api::search::search_index::search_messages;
+use api::search::semantic_index::rebuild_semantic_index;
+use api::search::semantic_index::semantic_search_chats;
+use api::settings::settings_storage::get_settings;
+use api::settings::settings_storage::update_settings;
+use api::tags::tag_storage::list_workspace_tags;
+use api::trash::trash_storage::list_trash;
+use api::trash::trash_storage::purge_expired_trash;
+use api::trash::trash_storage::purge_trash;
+use api::trash::trash_storage::restore_from_trash;
 use api::workspace::workspace_storage::create_workspace;
 use api::workspace::workspace_storage::delete_workspace;
 use api::workspace::workspace_storage::get_all_workspaces;
 use api::workspace::workspace_storage::rename_workspace;
 use api::workspace::workspace_storage::set_active_workspace;
+use api::workspace::workspace_storage::set_workspace_documents_dir;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .setup(|_app| {
+            // Drop trash items past their retention period before the UI loads
+            if let Err(e) = purge_expired_trash() {
+                eprintln!("Failed to purge expired trash: {}", e);
+            }
+            // Fix folder/chat cross-references left behind by interrupted operations
+            match check_data_integrity(true) {
+                Ok(report) if report.repaired => {
+                    eprintln!("Repaired {} data integrity issue(s)", report.issues.len());
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to check data integrity: {}", e),
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Models
             list_models,
@@ -45,23 +131,105 @@ pub fn run() {
             send_chat_message,
             get_all_chats,
             get_chats_for_workspace,
+            list_chats,
+            count_chats,
             get_chat_messages,
             rename_chat,
             delete_chat,
             search_chats,
+            set_chat_rag_enabled,
+            set_chat_model,
+            set_chat_pinned,
+            set_chat_starred,
+            set_chat_archived,
+            add_chat_tag,
+            remove_chat_tag,
+            move_chat_to_workspace,
+            duplicate_chat,
+            bulk_delete_chats,
+            bulk_move_chats,
+            bulk_tag_chats,
+            bulk_export_chats,
+            summarize_chat,
+            // Comparisons
+            compare_models,
+            list_comparisons,
+            get_comparison,
+            delete_comparison,
+            promote_comparison_result,
+            // Completions
+            generate_completion,
+            list_completion_sessions,
+            get_completion_session,
+            rename_completion_session,
+            delete_completion_session,
+            // Embeddings
+            embed_texts,
+            compute_similarity_matrix,
+            save_embedding_set,
+            list_embedding_sets,
+            get_embedding_set,
+            get_embedding_set_similarity,
+            delete_embedding_set,
+            // Search
+            search_messages,
+            rebuild_search_index,
+            semantic_search_chats,
+            rebuild_semantic_index,
+            // Presets
+            list_presets,
+            get_preset,
+            create_preset,
+            update_preset,
+            delete_preset,
+            export_presets,
+            import_presets,
+            // Prompts
+            list_prompts,
+            get_prompt,
+            create_prompt,
+            update_prompt,
+            delete_prompt,
+            render_prompt,
+            // Settings
+            get_settings,
+            update_settings,
             // Workspaces
             get_all_workspaces,
             create_workspace,
             rename_workspace,
             delete_workspace,
             set_active_workspace,
+            set_workspace_documents_dir,
+            backup_workspace,
+            restore_workspace,
+            // Documents (RAG)
+            index_workspace_documents,
             // Folders
             get_folders_for_workspace,
             create_folder,
             rename_folder,
             delete_folder,
+            move_folder,
+            move_folder_to_workspace,
             add_chat_to_folder,
             remove_chat_from_folder_cmd,
+            add_folder_tag,
+            remove_folder_tag,
+            // Tags
+            list_workspace_tags,
+            // Integrity
+            verify_data_integrity,
+            // Trash
+            list_trash,
+            restore_from_trash,
+            purge_trash,
+            // Export
+            export_chat,
+            export_folder,
+            export_workspace,
+            // Import
+            import_chats,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
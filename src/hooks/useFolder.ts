@@ -1,6 +1,20 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { FolderMeta } from "../types/folder";
+import type { FolderMeta, FolderNode } from "../types/folder";
+
+/**
+ * Flattens the folder tree returned by the backend into a depth-first list,
+ * so each folder is listed right after its parent.
+ *
+ * @param nodes - The folder tree from get_folders_for_workspace
+ * @returns Every folder in the tree, without the children arrays
+ */
+function flattenFolderTree(nodes: FolderNode[]): FolderMeta[] {
+  return nodes.flatMap(({ children, ...folder }) => [
+    folder,
+    ...flattenFolderTree(children),
+  ]);
+}
 
 /**
  * Custom hook for managing folder operations in the frontend.
@@ -25,10 +39,10 @@ export function useFolder(activeWorkspaceId: string) {
       const id = wsId ?? activeWorkspaceId;
       if (!id) return;
       try {
-        const result = await invoke<FolderMeta[]>("get_folders_for_workspace", {
+        const result = await invoke<FolderNode[]>("get_folders_for_workspace", {
           workspaceId: id,
         });
-        setFolders(result);
+        setFolders(flattenFolderTree(result));
       } catch (err) {
         console.error("Failed to fetch folders:", err);
       }
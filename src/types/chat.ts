@@ -2,14 +2,65 @@
 export interface ChatMessage {
   role: string;
   content: string;
+  created_at?: string;
+  citations?: MessageCitation[];
+  structured?: unknown;
+  thinking?: string;
+  model?: string;
+}
+
+// Represents a workspace document chunk supplied as RAG context for an assistant reply - used to render message sources
+export interface MessageCitation {
+  chunk_id: string;
+  source_path: string;
+  start_line: number;
+  end_line: number;
+  score: number;
+  cited: boolean;
 }
 
 // Represents a streaming chunk event from backend during chat generation - used in useChat streaming listener
 export interface ChatStreamEvent {
   chat_id: string;
   content: string;
+  thinking: string;
   done: boolean;
   done_reason: string | null;
+  context: ContextReport | null;
+}
+
+// Represents how the chat history was fitted into the model's context window - sent with the final stream chunk
+export interface ContextReport {
+  strategy: "sliding_window" | "keep_last" | "summarize";
+  context_window: number;
+  estimated_tokens: number;
+  exact_count: boolean;
+  total_messages: number;
+  sent_messages: number;
+  dropped_messages: number[];
+  summarized_messages: number;
+  summary_error: string | null;
+  overflow: boolean;
+  prompt_tokens: number | null;
+}
+
+// Represents optional per-request settings for send_chat_message - used when requesting structured JSON replies, sending a prompt template or starting from a preset
+export interface SendMessageOptions {
+  format?: "json" | Record<string, unknown>;
+  format_retries?: number;
+  think?: boolean;
+  template_id?: string;
+  variables?: Record<string, string>;
+  preset_id?: string;
+}
+
+// Represents the validated result of a structured JSON reply - used in useChat structured output listener
+export interface ChatStructuredEvent {
+  chat_id: string;
+  content: string;
+  value: unknown | null;
+  errors: string[];
+  attempts: number;
 }
 
 // Represents a streaming error event from backend - used in useChat error listener
@@ -24,8 +75,64 @@ export interface ChatMeta {
   chat_title: string;
   file_location: string;
   model_used: string;
+  models_used: string[];
   workspace_id: string;
   folder_id: string | null;
   created_at: string;
   last_updated_at: string;
+  rag_enabled: boolean;
+  custom_title: boolean;
+  summary: ChatSummary | null;
+  preset_id: string | null;
+  tags: string[];
+  pinned: boolean;
+  starred: boolean;
+  archived: boolean;
+}
+
+// Represents a meeting-note style digest of a chat - used in chat summary panel and exports
+export interface ChatSummary {
+  content: string;
+  model: string;
+  message_count: number;
+  created_at: string;
+}
+
+// Represents the outcome of a bulk chat operation for one chat - used in multi-select action results
+export interface BulkItemResult {
+  chat_id: string;
+  ok: boolean;
+  error: string | null;
+}
+
+// Represents the per-chat results of a bulk chat operation - returned by the bulk_* commands
+export interface BulkReport {
+  results: BulkItemResult[];
+  succeeded: number;
+  failed: number;
+}
+
+// Represents the field chats are ordered by - used in sidebar sort menu
+export type ChatSortKey = "created" | "updated" | "title" | "model";
+
+// Represents a sort direction - used in sidebar sort menu
+export type SortDirection = "asc" | "desc";
+
+// Represents a paginated chat listing request - passed to list_chats; omitted fields use the backend defaults
+export interface ChatListQuery {
+  workspace_id?: string | null;
+  folder_id?: string | null;
+  include_archived?: boolean;
+  sort?: ChatSortKey;
+  direction?: SortDirection;
+  pinned_first?: boolean | null;
+  cursor?: string | null;
+  limit?: number | null;
+}
+
+// Represents one page of chats - returned by list_chats; pass next_cursor back to load the following page
+export interface ChatPage {
+  chats: ChatMeta[];
+  next_cursor: string | null;
+  total: number;
 }
@@ -0,0 +1,65 @@
+// Represents timing and token counts for one compared model - used in the comparison view metrics row
+export interface ComparisonMetrics {
+  time_to_first_token_ms: number | null;
+  total_duration_ms: number | null;
+  load_duration_ms: number | null;
+  prompt_eval_count: number | null;
+  eval_count: number | null;
+  tokens_per_second: number | null;
+}
+
+// Represents one model's reply within a comparison - used in the comparison view columns
+export interface ComparisonResult {
+  model: string;
+  content: string;
+  thinking?: string;
+  done: boolean;
+  done_reason: string | null;
+  error: string | null;
+  metrics: ComparisonMetrics;
+  promoted_chat_id: string | null;
+}
+
+// Represents a prompt sent to several models with their replies - returned by get_comparison
+export interface Comparison {
+  id: string;
+  workspace_id: string;
+  prompt: string;
+  sequential: boolean;
+  results: ComparisonResult[];
+  created_at: string;
+  completed_at: string | null;
+}
+
+// Represents comparison metadata - used in the sidebar next to chats
+export interface ComparisonMeta {
+  id: string;
+  workspace_id: string;
+  prompt: string;
+  models: string[];
+  created_at: string;
+  completed_at: string | null;
+}
+
+// Represents a streaming chunk from one compared model - used in the comparison stream listener
+export interface ComparisonStreamEvent {
+  comparison_id: string;
+  model: string;
+  content: string;
+  thinking: string;
+  done: boolean;
+  done_reason: string | null;
+  metrics: ComparisonMetrics | null;
+}
+
+// Represents an error from one compared model - used in the comparison error listener
+export interface ComparisonStreamError {
+  comparison_id: string;
+  model: string;
+  error: string;
+}
+
+// Represents the end of a comparison once all models finished - used to reload the saved record
+export interface ComparisonCompleteEvent {
+  comparison_id: string;
+}
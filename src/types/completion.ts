@@ -0,0 +1,54 @@
+// Represents the parameters of a raw /api/generate request - used in the completion playground form
+export interface CompletionParams {
+  prompt: string;
+  suffix?: string | null;
+  system?: string | null;
+  template?: string | null;
+  raw?: boolean;
+  context?: number[] | null;
+  options?: Record<string, unknown> | null;
+}
+
+// Represents one generate request and its output - used in the completion playground history
+export interface CompletionRun {
+  id: string;
+  model: string;
+  params: CompletionParams;
+  response: string;
+  done_reason: string | null;
+  context?: number[];
+  prompt_eval_count: number | null;
+  eval_count: number | null;
+  created_at: string;
+}
+
+// Represents completion session metadata - used in the completion playground sidebar
+export interface CompletionSessionMeta {
+  id: string;
+  title: string;
+  model: string;
+  run_count: number;
+  created_at: string;
+  last_updated_at: string;
+}
+
+// Represents a completion session with all its runs - returned by get_completion_session
+export interface CompletionSession {
+  meta: CompletionSessionMeta;
+  runs: CompletionRun[];
+}
+
+// Represents a streaming chunk event during a completion - used in the completion stream listener
+export interface CompletionStreamEvent {
+  session_id: string;
+  content: string;
+  done: boolean;
+  done_reason: string | null;
+  context: number[] | null;
+}
+
+// Represents a streaming error event during a completion - used in the completion error listener
+export interface CompletionStreamError {
+  session_id: string;
+  error: string;
+}
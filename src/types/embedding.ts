@@ -0,0 +1,29 @@
+// Represents vectors returned by embed_texts - used in the embeddings playground
+export interface EmbedTextsResult {
+  model: string;
+  dimensions: number;
+  embeddings: number[][];
+  duration_ms: number;
+}
+
+// Represents a saved embedding set with its inputs and vectors - returned by get_embedding_set
+export interface EmbeddingSet {
+  id: string;
+  name: string;
+  model: string;
+  truncate: boolean | null;
+  dimensions: number | null;
+  inputs: string[];
+  embeddings: number[][];
+  created_at: string;
+}
+
+// Represents embedding set metadata without vectors - used in the embeddings playground set list
+export interface EmbeddingSetMeta {
+  id: string;
+  name: string;
+  model: string;
+  input_count: number;
+  vector_size: number;
+  created_at: string;
+}
@@ -3,8 +3,17 @@ export interface FolderMeta {
   id: string;
   name: string;
   workspace_id: string;
+  parent_id: string | null;
   chat_ids: string[];
   tags: string[];
   created_at: string;
   last_updated_at: string;
 }
+
+// Represents a folder with its subfolders - returned by get_folders_for_workspace
+export interface FolderNode extends FolderMeta {
+  children: FolderNode[];
+}
+
+// Represents what happens to a deleted folder's chats - passed to delete_folder
+export type FolderChatPolicy = "release" | "move_to_parent";
@@ -0,0 +1,22 @@
+// Represents the kinds of inconsistency found by verify_data_integrity
+export type IntegrityIssueKind =
+  | "dangling_chat_id"
+  | "folder_mismatch"
+  | "missing_folder"
+  | "missing_workspace"
+  | "orphaned_chat_file"
+  | "missing_chat_file";
+
+// Represents a single inconsistency - used in the data integrity panel in settings
+export interface IntegrityIssue {
+  kind: IntegrityIssueKind;
+  subject_id: string;
+  detail: string;
+}
+
+// Represents the result of verify_data_integrity - used in the data integrity panel in settings
+export interface IntegrityReport {
+  issues: IntegrityIssue[];
+  repaired: boolean;
+  checked_at: string;
+}
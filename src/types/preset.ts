@@ -0,0 +1,17 @@
+// Represents the editable settings of a preset - sent to create_preset/update_preset and stored in preset export files
+export interface PresetInput {
+  name: string;
+  description: string | null;
+  model: string;
+  system_prompt: string | null;
+  options: Record<string, unknown> | null;
+  tools: string[];
+  keep_alive: string | number | null;
+}
+
+// Represents a saved preset applied when starting a chat - used in the preset picker and settings
+export interface Preset extends PresetInput {
+  id: string;
+  created_at: string;
+  last_updated_at: string;
+}
@@ -0,0 +1,12 @@
+// Represents a reusable prompt with {{variable}} placeholders - used in the prompt library and when sending a templated message
+export interface PromptTemplate {
+  id: string;
+  name: string;
+  description: string | null;
+  content: string;
+  workspace_id: string | null;
+  variables: string[];
+  defaults: Record<string, string>;
+  created_at: string;
+  last_updated_at: string;
+}
@@ -0,0 +1,6 @@
+// Represents how often a tag is used in a workspace - returned by list_workspace_tags for the tag filter
+export interface TagCount {
+  tag: string;
+  folder_count: number;
+  chat_count: number;
+}
@@ -4,6 +4,7 @@ export interface WorkspaceMeta {
   name: string;
   created_at: string;
   last_updated_at: string;
+  documents_dir: string | null;
 }
 
 // Represents the workspaces index with all workspaces and active ID - used in useWorkspace state